@@ -0,0 +1,115 @@
+use crate::game::{Bitboard, Board, Color, PieceType, Position};
+
+use super::pawns::passed_span;
+
+/// Bonus for each square between the enemy king and the square in front of
+/// a passed pawn, in endgame centipawns, multiplied by how far the pawn has
+/// advanced past its second rank
+const ENEMY_KING_DISTANCE: i32 = 5;
+
+/// Penalty for each square between a player's own king and the square in
+/// front of their passed pawn, multiplied in the same way
+const OWN_KING_DISTANCE: i32 = 2;
+
+/// Bonus for a passed pawn that the enemy king can't catch, when the enemy
+/// has no pieces to stop it either, in endgame centipawns
+const UNSTOPPABLE: i32 = 500;
+
+/// Returns the number of king moves between two squares
+fn distance(a: Position, b: Position) -> i32 {
+    (a.row() - b.row()).abs().max((a.col() - b.col()).abs()) as i32
+}
+
+/// Score of the kings' distances to the passed pawns, and of any passed
+/// pawns that can't be stopped from promoting, in endgame centipawns from
+/// White's point of view
+pub(super) fn passers(board: &Board) -> i32 {
+    let mut score = 0;
+    // Fewest plies before each player promotes an unstoppable pawn
+    let mut fastest = [None; 2];
+    for color in [Color::White, Color::Black] {
+        let sign = if color == Color::White { 1 } else { -1 };
+        let (Some(own_king), Some(enemy_king)) = (
+            board.pieces(PieceType::King, color).first(),
+            board.pieces(PieceType::King, !color).first(),
+        ) else {
+            return 0;
+        };
+        let enemy_pawns = board.pieces(PieceType::Pawn, !color);
+        let enemy_pieces =
+            board.occupied_by(!color) & !enemy_pawns & !board.pieces(PieceType::King, !color);
+        let to_move = board.whose_turn() == color;
+        for pawn in board.pieces(PieceType::Pawn, color) {
+            if !(enemy_pawns & passed_span(pawn, color)).is_empty() {
+                continue;
+            }
+            let rank = match color {
+                Color::White => pawn.row(),
+                Color::Black => 7 - pawn.row(),
+            };
+            let Some(stop) = pawn.offset(color.get_direction(), 0) else {
+                continue;
+            };
+            // The kings matter more the closer the pawn is to promoting
+            let advanced = (rank - 1).max(0) as i32;
+            score += sign
+                * advanced
+                * (ENEMY_KING_DISTANCE * distance(enemy_king, stop)
+                    - OWN_KING_DISTANCE * distance(own_king, stop));
+
+            // By the rule of the square, the enemy king can only catch the
+            // pawn if it can reach the promotion square in time, counting
+            // the double step from the second rank
+            if !enemy_pieces.is_empty()
+                || !(board.occupied() & Bitboard::forward_span(pawn, color)).is_empty()
+            {
+                continue;
+            }
+            let promotion = Position::new((!color).get_home(), pawn.col());
+            let moves = (7 - rank).min(5) as i32;
+            let king_moves = distance(enemy_king, promotion) - !to_move as i32;
+            if king_moves > moves {
+                let plies = 2 * moves - to_move as i32;
+                let fastest = &mut fastest[color.index()];
+                *fastest = Some(fastest.map_or(plies, |other: i32| other.min(plies)));
+            }
+        }
+    }
+    // If both players have a pawn that can't be caught, the first to
+    // promote wins the race
+    match (fastest[Color::White.index()], fastest[Color::Black.index()]) {
+        (Some(white), Some(black)) if white < black => score + UNSTOPPABLE,
+        (Some(white), Some(black)) if black < white => score - UNSTOPPABLE,
+        (Some(_), None) => score + UNSTOPPABLE,
+        (None, Some(_)) => score - UNSTOPPABLE,
+        _ => score,
+    }
+}
+
+/// Returns whether the position is a known draw despite the material: a
+/// king, bishops and pawns only on the a or h file against a lone king that
+/// holds the corner the pawns promote on, which the bishops can't cover as
+/// they stand on squares of the other color
+pub(super) fn is_wrong_bishop_draw(board: &Board) -> bool {
+    [Color::White, Color::Black].into_iter().any(|strong| {
+        let pawns = board.pieces(PieceType::Pawn, strong);
+        let bishops = board.pieces(PieceType::Bishop, strong);
+        let king = board.pieces(PieceType::King, strong);
+        let weak_king = board.pieces(PieceType::King, !strong).first();
+        let Some(col) = [0, 7]
+            .into_iter()
+            .find(|&col| (pawns & !Bitboard::file(col)).is_empty())
+        else {
+            return false;
+        };
+        let promotion = Position::new((!strong).get_home(), col);
+        board.occupied_by(!strong).count() == 1
+            && board.occupied_by(strong) == pawns | bishops | king
+            && !pawns.is_empty()
+            && !bishops.is_empty()
+            && bishops
+                .into_iter()
+                .all(|pos| pos.color() != promotion.color())
+            && weak_king.is_some_and(|pos| distance(pos, promotion) <= 1)
+    })
+}
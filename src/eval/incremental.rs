@@ -0,0 +1,145 @@
+use alloc::{vec, vec::Vec};
+use core::cell::RefCell;
+
+use crate::game::{Board, Color, PieceType, Position, Turn};
+
+use super::{
+    endgame::passers, king_safety::king_safety, piece_sums, piece_value, pst, taper, Evaluator,
+    KingSafetyWeights, PawnTable, PhaseWeights,
+};
+
+/// A piece added to or removed from a square by a turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceChange {
+    Added(PieceType, Color, Position),
+    Removed(PieceType, Color, Position),
+}
+
+/// Returns every piece the turn adds to or removes from the board, given
+/// the board before it is made, for evaluators to update their state with
+pub fn piece_changes(board: &Board, turn: &Turn) -> impl Iterator<Item = PieceChange> {
+    let color = board.whose_turn();
+    let captured = turn.capture.and_then(|capture| {
+        let piece = board.at_position(capture)?;
+        Some(PieceChange::Removed(piece.kind, piece.color, capture))
+    });
+    let (rook_from, rook_to) = match turn.additional_move {
+        Some((from, to)) => match board.at_position(from) {
+            Some(piece) => (
+                Some(PieceChange::Removed(piece.kind, piece.color, from)),
+                Some(PieceChange::Added(piece.kind, piece.color, to)),
+            ),
+            None => (None, None),
+        },
+        None => (None, None),
+    };
+    [
+        captured,
+        Some(PieceChange::Removed(turn.kind, color, turn.from)),
+        rook_from,
+        rook_to,
+        Some(PieceChange::Added(
+            turn.promote_to.unwrap_or(turn.kind),
+            color,
+            turn.to,
+        )),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// Sums making up the built-in evaluation, from White's point of view
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Accumulator {
+    middlegame: i32,
+    endgame: i32,
+    phase: i32,
+}
+
+impl Accumulator {
+    fn new(board: &Board, weights: &PhaseWeights) -> Self {
+        let (middlegame, endgame, phase) = piece_sums(board, weights);
+        Self {
+            middlegame,
+            endgame,
+            phase,
+        }
+    }
+
+    fn apply(&mut self, change: PieceChange, weights: &PhaseWeights) {
+        let (kind, color, pos, delta) = match change {
+            PieceChange::Added(kind, color, pos) => (kind, color, pos, 1),
+            PieceChange::Removed(kind, color, pos) => (kind, color, pos, -1),
+        };
+        let (mg, eg) = pst::bonus(kind, color, pos);
+        let sign = if color == Color::White { delta } else { -delta };
+        self.middlegame += sign * (piece_value(kind) + mg);
+        self.endgame += sign * (piece_value(kind) + eg);
+        self.phase += delta * weights.weight(kind);
+    }
+}
+
+/// The built-in evaluation, giving the same scores as `Classical`, but
+/// updating its material and piece-square sums as turns are made and
+/// undone, rather than adding them up for every position.
+///
+/// Without a position given to `reset`, it adds them up like `Classical`.
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalClassical {
+    /// Sums for each position from the one given to `reset`, with the
+    /// current position last
+    stack: Vec<Accumulator>,
+
+    pub king_safety: KingSafetyWeights,
+
+    /// Weights of the game phase, which must not be changed between `reset`
+    /// and the end of the search, as the phase is kept as turns are made
+    pub phase: PhaseWeights,
+    pawns: RefCell<PawnTable>,
+}
+
+impl IncrementalClassical {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Evaluator for IncrementalClassical {
+    fn evaluate(&self, board: &Board) -> i32 {
+        let accumulator = match self.stack.last() {
+            Some(&accumulator) => accumulator,
+            None => Accumulator::new(board, &self.phase),
+        };
+        let (pawn_mg, pawn_eg) = self.pawns.borrow_mut().probe(board);
+        let king_safety = king_safety(board, &self.king_safety);
+        taper(
+            board,
+            accumulator.middlegame + pawn_mg + king_safety,
+            accumulator.endgame + pawn_eg + passers(board),
+            accumulator.phase,
+            &self.phase,
+        )
+    }
+
+    fn reset(&mut self, board: &Board) {
+        self.stack = vec![Accumulator::new(board, &self.phase)];
+    }
+
+    fn make_turn(&mut self, board: &Board, turn: &Turn) {
+        let Some(&accumulator) = self.stack.last() else {
+            return;
+        };
+        let mut accumulator = accumulator;
+        for change in piece_changes(board, turn) {
+            accumulator.apply(change, &self.phase);
+        }
+        self.stack.push(accumulator);
+    }
+
+    fn undo_turn(&mut self) {
+        // Keep the position given to `reset`
+        if self.stack.len() > 1 {
+            self.stack.pop();
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::game::{
+    bishop_attacks, knight_attacks, rook_attacks, Bitboard, Board, Color, PieceType, Position,
+    PIECE_TYPES,
+};
+
+/// Weights of the king safety term, which only counts towards the
+/// middlegame score, exposed so they can be tuned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KingSafetyWeights {
+    /// Bonus for each pawn straight in front of the king, or diagonally in
+    /// front of it, while the king is on its first two ranks
+    pub shield_near: i32,
+
+    /// Bonus for each shield pawn one square further forward
+    pub shield_far: i32,
+
+    /// Penalty for each file at or next to the king with no pawns on it
+    pub open_file: i32,
+
+    /// Penalty for each file at or next to the king with only enemy pawns
+    /// on it
+    pub half_open_file: i32,
+
+    /// Weight of each square around the king attacked by an enemy piece,
+    /// indexed by `PieceType::index`
+    pub attacker_weights: [i32; 6],
+
+    /// Percentage of the attack weight counted, indexed by the number of
+    /// enemy pieces attacking the squares around the king, as a lone
+    /// attacker is rarely dangerous
+    pub attacker_scale: [i32; 8],
+}
+
+impl Default for KingSafetyWeights {
+    fn default() -> Self {
+        Self {
+            shield_near: 10,
+            shield_far: 5,
+            open_file: 20,
+            half_open_file: 10,
+            attacker_weights: [0, 10, 6, 4, 4, 0],
+            attacker_scale: [0, 0, 50, 75, 88, 94, 97, 99],
+        }
+    }
+}
+
+/// Score of the safety of both kings, in middlegame centipawns from White's
+/// point of view
+pub(super) fn king_safety(board: &Board, weights: &KingSafetyWeights) -> i32 {
+    side(board, Color::White, weights) - side(board, Color::Black, weights)
+}
+
+/// Score of the safety of one player's king, in middlegame centipawns from
+/// their point of view
+fn side(board: &Board, color: Color, weights: &KingSafetyWeights) -> i32 {
+    let Some(king) = board.pieces(PieceType::King, color).first() else {
+        return 0;
+    };
+    let own_pawns = board.pieces(PieceType::Pawn, color);
+    let enemy_pawns = board.pieces(PieceType::Pawn, !color);
+    let forward = match color {
+        Color::White => 1,
+        Color::Black => -1,
+    };
+    let rank = match color {
+        Color::White => king.row(),
+        Color::Black => 7 - king.row(),
+    };
+    let mut score = 0;
+    for col in (king.col() - 1).max(0)..=(king.col() + 1).min(7) {
+        let on_file = |pawns: Bitboard| !(pawns & Bitboard::file(col)).is_empty();
+        match (on_file(own_pawns), on_file(enemy_pawns)) {
+            (false, false) => score -= weights.open_file,
+            (false, true) => score -= weights.half_open_file,
+            _ => {}
+        }
+        if rank > 1 {
+            continue;
+        }
+        let shield = |rows: i8| {
+            let row = king.row() + rows * forward;
+            (0..8).contains(&row) && own_pawns.contains(Position::new(row, col))
+        };
+        if shield(1) {
+            score += weights.shield_near;
+        } else if shield(2) {
+            score += weights.shield_far;
+        }
+    }
+    // Attacks on the squares the king could move to
+    let ring = board.king_zone(color);
+    let occupied = board.occupied();
+    let mut attackers = 0;
+    let mut attack = 0;
+    for kind in PIECE_TYPES {
+        let weight = weights.attacker_weights[kind.index()];
+        if weight == 0 {
+            continue;
+        }
+        for pos in board.pieces(kind, !color) {
+            let attacked = match kind {
+                PieceType::Queen => rook_attacks(pos, occupied) | bishop_attacks(pos, occupied),
+                PieceType::Rook => rook_attacks(pos, occupied),
+                PieceType::Bishop => bishop_attacks(pos, occupied),
+                PieceType::Knight => knight_attacks(pos),
+                _ => Bitboard::EMPTY,
+            } & ring;
+            if !attacked.is_empty() {
+                attackers += 1;
+                attack += weight * attacked.count() as i32;
+            }
+        }
+    }
+    let scale = weights.attacker_scale[attackers.min(weights.attacker_scale.len() - 1)];
+    score - attack * scale / 100
+}
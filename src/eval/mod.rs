@@ -0,0 +1,202 @@
+//! Static evaluation of positions, without searching.
+//!
+//! Scores are given in centipawns, from the point of view of the player
+//! whose turn it is, as used by `search`.
+
+mod endgame;
+mod incremental;
+mod king_safety;
+mod pawns;
+mod pst;
+
+use core::cell::RefCell;
+
+use crate::game::{Board, Color, PieceType, Turn, PIECE_TYPES};
+
+pub use incremental::{piece_changes, IncrementalClassical, PieceChange};
+pub use king_safety::KingSafetyWeights;
+pub use pawns::{PawnTable, DEFAULT_PAWN_TABLE_SIZE};
+
+/// Bonus for being the player to move
+pub const TEMPO: i32 = 10;
+
+/// How much each kind of piece counts towards the game phase, which falls
+/// as pieces are traded, moving the score from its middlegame to its
+/// endgame value. Exposed so they can be tuned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseWeights {
+    /// Weight of each piece, indexed by `PieceType::index`
+    pub weights: [i32; 6],
+}
+
+impl Default for PhaseWeights {
+    /// Each knight and bishop counts 1, each rook 2 and each queen 4
+    fn default() -> Self {
+        Self {
+            weights: [0, 4, 2, 1, 1, 0],
+        }
+    }
+}
+
+impl PhaseWeights {
+    /// Returns how much a kind of piece counts towards the game phase
+    pub fn weight(&self, kind: PieceType) -> i32 {
+        self.weights[kind.index()]
+    }
+
+    /// Returns the game phase with all the pieces of the starting position
+    /// on the board, at or above which only the middlegame score counts
+    pub fn max_phase(&self) -> i32 {
+        use PieceType::*;
+        let per_side = [
+            (King, 1),
+            (Queen, 1),
+            (Rook, 2),
+            (Bishop, 2),
+            (Knight, 2),
+            (Pawn, 8),
+        ]
+        .into_iter()
+        .map(|(kind, count)| self.weight(kind) * count)
+        .sum::<i32>();
+        2 * per_side
+    }
+}
+
+/// Scores positions for a search, in centipawns from the point of view of
+/// the player to move.
+///
+/// This is implemented for any function or closure taking a board, so a
+/// custom evaluation can be given to a search without defining a type.
+///
+/// Evaluators that keep state between positions, such as running sums of
+/// their terms, can update it as the search goes: `reset` is called with
+/// the position a search starts from, then `make_turn` before each turn is
+/// made, and `undo_turn` after each is undone. Null moves don't move any
+/// pieces, so aren't passed on. These do nothing by default.
+pub trait Evaluator {
+    fn evaluate(&self, board: &Board) -> i32;
+
+    /// Set up any state for the given position
+    fn reset(&mut self, _board: &Board) {}
+
+    /// Update any state for the given turn, which is about to be made on
+    /// the board
+    fn make_turn(&mut self, _board: &Board, _turn: &Turn) {}
+
+    /// Restore any state to how it was before the last turn given to
+    /// `make_turn`
+    fn undo_turn(&mut self) {}
+}
+
+impl<F: Fn(&Board) -> i32> Evaluator for F {
+    fn evaluate(&self, board: &Board) -> i32 {
+        self(board)
+    }
+}
+
+/// The built-in evaluation, as given by `evaluate`, keeping pawn structure
+/// scores in a `PawnTable` so they aren't worked out again for every
+/// position
+#[derive(Debug, Clone, Default)]
+pub struct Classical {
+    pub king_safety: KingSafetyWeights,
+    pub phase: PhaseWeights,
+    pawns: RefCell<PawnTable>,
+}
+
+impl Classical {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Evaluator for Classical {
+    fn evaluate(&self, board: &Board) -> i32 {
+        let (middlegame, endgame, phase) = piece_sums(board, &self.phase);
+        let (pawn_mg, pawn_eg) = self.pawns.borrow_mut().probe(board);
+        let king_safety = king_safety::king_safety(board, &self.king_safety);
+        taper(
+            board,
+            middlegame + pawn_mg + king_safety,
+            endgame + pawn_eg + endgame::passers(board),
+            phase,
+            &self.phase,
+        )
+    }
+}
+
+/// Returns the value of a kind of piece in centipawns
+pub fn piece_value(kind: PieceType) -> i32 {
+    match kind {
+        PieceType::King => 0,
+        PieceType::Queen => 900,
+        PieceType::Rook => 500,
+        PieceType::Bishop => 330,
+        PieceType::Knight => 320,
+        PieceType::Pawn => 100,
+    }
+}
+
+/// Score a position by its material, where each piece stands, its pawn
+/// structure and the safety of the kings, in centipawns from the point of
+/// view of the player to move.
+///
+/// The piece-square bonuses and pawn structure terms move from middlegame
+/// to endgame values as pieces are traded, and king safety fades out
+/// entirely. In the endgame, the kings' distances to passed pawns count,
+/// along with passed pawns the enemy king can't catch, and a bishop that
+/// can't help a rook pawn promote scores as a draw.
+pub fn evaluate(board: &Board) -> i32 {
+    let phase_weights = PhaseWeights::default();
+    let (middlegame, endgame, phase) = piece_sums(board, &phase_weights);
+    let (pawn_mg, pawn_eg) = pawns::structure(board);
+    let king_safety = king_safety::king_safety(board, &KingSafetyWeights::default());
+    taper(
+        board,
+        middlegame + pawn_mg + king_safety,
+        endgame + pawn_eg + endgame::passers(board),
+        phase,
+        &phase_weights,
+    )
+}
+
+/// Returns the sums of the material and piece-square bonuses, as
+/// middlegame and endgame centipawns from White's point of view, along
+/// with the game phase
+fn piece_sums(board: &Board, weights: &PhaseWeights) -> (i32, i32, i32) {
+    let mut middlegame = 0;
+    let mut endgame = 0;
+    let mut phase = 0;
+    for color in [Color::White, Color::Black] {
+        let sign = if color == Color::White { 1 } else { -1 };
+        for kind in PIECE_TYPES {
+            for pos in board.pieces(kind, color) {
+                let (mg, eg) = pst::bonus(kind, color, pos);
+                middlegame += sign * (piece_value(kind) + mg);
+                endgame += sign * (piece_value(kind) + eg);
+                phase += weights.weight(kind);
+            }
+        }
+    }
+    (middlegame, endgame, phase)
+}
+
+/// Blend middlegame and endgame scores from White's point of view by the
+/// game phase, giving the final score for the player to move, or a draw if
+/// the position is known to be one whatever the scores
+fn taper(board: &Board, middlegame: i32, endgame: i32, phase: i32, weights: &PhaseWeights) -> i32 {
+    if endgame::is_wrong_bishop_draw(board) {
+        return 0;
+    }
+    // Without any weights, the phase is always the endgame
+    let max_phase = weights.max_phase().max(1);
+    let phase = phase.clamp(0, max_phase);
+    let score = (middlegame * phase + endgame * (max_phase - phase)) / max_phase;
+    let sign = if board.whose_turn() == Color::White {
+        1
+    } else {
+        -1
+    };
+    sign * score + TEMPO
+}
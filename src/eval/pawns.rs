@@ -0,0 +1,155 @@
+use alloc::{vec, vec::Vec};
+
+use crate::game::{pawn_attacks, Bitboard, Board, Color, PieceType, Position};
+
+/// Penalty for each pawn on a file beyond the first, as middlegame and
+/// endgame centipawns
+const DOUBLED: (i32, i32) = (10, 20);
+
+/// Penalty for a pawn with no pawns of its own color on the files next to
+/// it
+const ISOLATED: (i32, i32) = (10, 15);
+
+/// Penalty for a pawn behind the pawns of its own color on the files next
+/// to it, which can't safely advance as an enemy pawn guards the square in
+/// front of it
+const BACKWARD: (i32, i32) = (8, 10);
+
+/// Bonus for a pawn with no enemy pawns in front of it on its own file or
+/// the files next to it, indexed by how far up the board it is from its
+/// player's point of view
+const PASSED: [(i32, i32); 8] = [
+    (0, 0),
+    (5, 10),
+    (10, 15),
+    (15, 25),
+    (30, 50),
+    (50, 90),
+    (80, 140),
+    (0, 0),
+];
+
+/// Number of entries in a pawn table created with `PawnTable::new`
+pub const DEFAULT_PAWN_TABLE_SIZE: usize = 1 << 14;
+
+/// Score of the pawn structure, as middlegame and endgame centipawns from
+/// White's point of view
+pub(super) fn structure(board: &Board) -> (i32, i32) {
+    let (white_mg, white_eg) = side(board, Color::White);
+    let (black_mg, black_eg) = side(board, Color::Black);
+    (white_mg - black_mg, white_eg - black_eg)
+}
+
+/// Score of the pawn structure of one player, as middlegame and endgame
+/// centipawns from their point of view
+fn side(board: &Board, color: Color) -> (i32, i32) {
+    let own = board.pieces(PieceType::Pawn, color);
+    let enemy = board.pieces(PieceType::Pawn, !color);
+    // Distance up the board from the player's point of view
+    let rank = |pos: Position| match color {
+        Color::White => pos.row(),
+        Color::Black => 7 - pos.row(),
+    };
+    let forward = match color {
+        Color::White => 1,
+        Color::Black => -1,
+    };
+    let mut score = (0, 0);
+    let mut add = |(mg, eg): (i32, i32), sign: i32| {
+        score.0 += sign * mg;
+        score.1 += sign * eg;
+    };
+    for col in 0..8 {
+        let count = (own & Bitboard::file(col)).count() as i32;
+        if count > 1 {
+            add(DOUBLED, 1 - count);
+        }
+    }
+    for pawn in own {
+        let neighbours = own & Bitboard::adjacent_files(pawn.col());
+        let passed = (enemy & passed_span(pawn, color)).is_empty();
+        if passed {
+            add(PASSED[rank(pawn) as usize], 1);
+        }
+        if neighbours.is_empty() {
+            add(ISOLATED, -1);
+            continue;
+        }
+        // Enemy pawns that guard the square in front of this one
+        let stop_guarded = pawn
+            .offset(forward, 0)
+            .is_some_and(|stop| !(enemy & pawn_attacks(color, stop)).is_empty());
+        let behind = neighbours.into_iter().all(|pos| rank(pos) > rank(pawn));
+        if !passed && behind && stop_guarded {
+            add(BACKWARD, -1);
+        }
+    }
+    score
+}
+
+/// Returns the squares in front of a pawn of the given color on its own file
+/// and the files next to it, which no enemy pawn may stand on for it to be
+/// passed
+pub(super) fn passed_span(pawn: Position, color: Color) -> Bitboard {
+    [-1, 0, 1]
+        .into_iter()
+        .filter_map(|col| pawn.offset(0, col))
+        .fold(Bitboard::EMPTY, |span, pos| {
+            span | Bitboard::forward_span(pos, color)
+        })
+}
+
+/// Cache of pawn structure scores, keyed by `Board::pawn_hash`, as the
+/// pawns change far less often than the rest of the position during a
+/// search
+#[derive(Debug, Clone)]
+pub struct PawnTable {
+    /// Entries of pawn hash and score, indexed by the low bits of the hash
+    entries: Vec<Option<(u64, (i32, i32))>>,
+}
+
+impl Default for PawnTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PawnTable {
+    pub fn new() -> Self {
+        Self::with_size(DEFAULT_PAWN_TABLE_SIZE)
+    }
+
+    /// Create a table with at least one entry, rounding the number of
+    /// entries down to a power of two
+    pub fn with_size(entries: usize) -> Self {
+        let entries = if entries == 0 {
+            1
+        } else {
+            1 << entries.ilog2()
+        };
+        Self {
+            entries: vec![None; entries],
+        }
+    }
+
+    /// Remove every entry
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+
+    /// Returns the score of the pawn structure, as middlegame and endgame
+    /// centipawns from White's point of view, working it out and storing it
+    /// if it isn't in the table
+    pub fn probe(&mut self, board: &Board) -> (i32, i32) {
+        let key = board.pawn_hash();
+        let index = key as usize & (self.entries.len() - 1);
+        match self.entries[index] {
+            Some((entry_key, score)) if entry_key == key => score,
+            _ => {
+                let score = structure(board);
+                self.entries[index] = Some((key, score));
+                score
+            }
+        }
+    }
+}
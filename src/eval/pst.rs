@@ -0,0 +1,192 @@
+//! Piece-square tables, giving a bonus in centipawns for a piece standing on
+//! each square.
+//!
+//! Each kind of piece has a middlegame and an endgame table, which are
+//! blended by the game phase. Tables are laid out as seen from White's side
+//! of the board, so the first row is the eighth rank.
+
+use crate::game::{Color, PieceType, Position};
+
+/// Pawns should take the centre, and stay in front of a castled king
+#[rustfmt::skip]
+const PAWN_MIDDLEGAME: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    10, 10, 20, 30, 30, 20, 10, 10,
+     5,  5, 10, 25, 25, 10,  5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+/// Once the pieces are traded, pawns should advance to promote wherever
+/// they are
+#[rustfmt::skip]
+const PAWN_ENDGAME: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    80, 80, 80, 80, 80, 80, 80, 80,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    30, 30, 30, 30, 30, 30, 30, 30,
+    15, 15, 15, 15, 15, 15, 15, 15,
+     5,  5,  5,  5,  5,  5,  5,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+/// Knights need to be central to reach anything
+#[rustfmt::skip]
+const KNIGHT_MIDDLEGAME: [i32; 64] = [
+    -50,-40,-30,-30,-30,-30,-40,-50,
+    -40,-20,  0,  0,  0,  0,-20,-40,
+    -30,  0, 10, 15, 15, 10,  0,-30,
+    -30,  5, 15, 20, 20, 15,  5,-30,
+    -30,  0, 15, 20, 20, 15,  0,-30,
+    -30,  5, 10, 15, 15, 10,  5,-30,
+    -40,-20,  0,  5,  5,  0,-20,-40,
+    -50,-40,-30,-30,-30,-30,-40,-50,
+];
+
+/// Knights still want to be central in the endgame, though the edges
+/// matter less with fewer pieces about
+#[rustfmt::skip]
+const KNIGHT_ENDGAME: [i32; 64] = [
+    -40,-30,-20,-20,-20,-20,-30,-40,
+    -30,-15, -5,  0,  0, -5,-15,-30,
+    -20, -5, 10, 15, 15, 10, -5,-20,
+    -20,  0, 15, 20, 20, 15,  0,-20,
+    -20,  0, 15, 20, 20, 15,  0,-20,
+    -20, -5, 10, 15, 15, 10, -5,-20,
+    -30,-15, -5,  0,  0, -5,-15,-30,
+    -40,-30,-20,-20,-20,-20,-30,-40,
+];
+
+/// Bishops should be developed onto long diagonals
+#[rustfmt::skip]
+const BISHOP_MIDDLEGAME: [i32; 64] = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  5,  5, 10, 10,  5,  5,-10,
+    -10,  0, 10, 10, 10, 10,  0,-10,
+    -10, 10, 10, 10, 10, 10, 10,-10,
+    -10,  5,  0,  0,  0,  0,  5,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+/// With fewer pieces in the way, bishops mostly want to avoid the edges
+#[rustfmt::skip]
+const BISHOP_ENDGAME: [i32; 64] = [
+    -15,-10,-10,-10,-10,-10,-10,-15,
+    -10, -5,  0,  0,  0,  0, -5,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+    -10, -5,  0,  0,  0,  0, -5,-10,
+    -15,-10,-10,-10,-10,-10,-10,-15,
+];
+
+/// Rooks belong on the central files and the seventh rank
+#[rustfmt::skip]
+const ROOK_MIDDLEGAME: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10, 10, 10, 10, 10,  5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+
+/// Rooks are about as useful anywhere in the endgame, other than the
+/// seventh rank, where they cut off the enemy king
+#[rustfmt::skip]
+const ROOK_ENDGAME: [i32; 64] = [
+     5,  5,  5,  5,  5,  5,  5,  5,
+    10, 10, 10, 10, 10, 10, 10, 10,
+     0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+/// The queen shouldn't come out too early, or stray to the edges
+#[rustfmt::skip]
+const QUEEN_MIDDLEGAME: [i32; 64] = [
+    -20,-10,-10, -5, -5,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+     -5,  0,  5,  5,  5,  5,  0, -5,
+      0,  0,  5,  5,  5,  5,  0, -5,
+    -10,  5,  5,  5,  5,  5,  0,-10,
+    -10,  0,  5,  0,  0,  0,  0,-10,
+    -20,-10,-10, -5, -5,-10,-10,-20,
+];
+
+/// A centralised queen attacks the most squares once the pieces are traded
+#[rustfmt::skip]
+const QUEEN_ENDGAME: [i32; 64] = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+    -10,  5, 10, 10, 10, 10,  5,-10,
+    -10,  5, 10, 15, 15, 10,  5,-10,
+    -10,  5, 10, 15, 15, 10,  5,-10,
+    -10,  5, 10, 10, 10, 10,  5,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+/// The king should hide behind its pawns while there are pieces to attack
+/// it
+#[rustfmt::skip]
+const KING_MIDDLEGAME: [i32; 64] = [
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -20,-30,-30,-40,-40,-30,-30,-20,
+    -10,-20,-20,-20,-20,-20,-20,-10,
+     20, 20,  0,  0,  0,  0, 20, 20,
+     20, 30, 10,  0,  0, 10, 30, 20,
+];
+
+/// Once the pieces are traded, the king should head for the centre
+#[rustfmt::skip]
+const KING_ENDGAME: [i32; 64] = [
+    -50,-40,-30,-20,-20,-30,-40,-50,
+    -30,-20,-10,  0,  0,-10,-20,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 30, 40, 40, 30,-10,-30,
+    -30,-10, 20, 30, 30, 20,-10,-30,
+    -30,-30,  0,  0,  0,  0,-30,-30,
+    -50,-30,-30,-30,-30,-30,-30,-50,
+];
+
+/// Returns the index into the tables of a square for a piece of the given
+/// color, mirroring the board for Black
+fn index(color: Color, pos: Position) -> usize {
+    let row = match color {
+        Color::White => 7 - pos.row(),
+        Color::Black => pos.row(),
+    };
+    (row * 8 + pos.col()) as usize
+}
+
+/// Returns the middlegame and endgame bonuses for a piece standing on the
+/// given square
+pub(super) fn bonus(kind: PieceType, color: Color, pos: Position) -> (i32, i32) {
+    let i = index(color, pos);
+    match kind {
+        PieceType::King => (KING_MIDDLEGAME[i], KING_ENDGAME[i]),
+        PieceType::Queen => (QUEEN_MIDDLEGAME[i], QUEEN_ENDGAME[i]),
+        PieceType::Rook => (ROOK_MIDDLEGAME[i], ROOK_ENDGAME[i]),
+        PieceType::Bishop => (BISHOP_MIDDLEGAME[i], BISHOP_ENDGAME[i]),
+        PieceType::Knight => (KNIGHT_MIDDLEGAME[i], KNIGHT_ENDGAME[i]),
+        PieceType::Pawn => (PAWN_MIDDLEGAME[i], PAWN_ENDGAME[i]),
+    }
+}
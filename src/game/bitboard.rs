@@ -0,0 +1,363 @@
+use core::{
+    fmt::Display,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not},
+};
+
+use super::{piece::KNIGHT_MOVES, Color, Position};
+
+#[cfg(feature = "std")]
+pub use super::magic::{bishop_attacks, rook_attacks};
+
+pub(super) const ROOK_DIRECTIONS: [(i8, i8); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+pub(super) const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+/// A set of squares, stored as one bit for each square, where bit `n` is the
+/// square with `Position::pos() == n`.
+///
+/// Set operations are done with the usual bitwise operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    /// The set containing no squares
+    pub const EMPTY: Bitboard = Bitboard(0);
+
+    /// The set containing only the given square
+    pub fn from_position(pos: Position) -> Self {
+        Bitboard(1 << pos.pos())
+    }
+
+    /// Returns whether the given square is in the set
+    pub fn contains(self, pos: Position) -> bool {
+        self.0 & (1 << pos.pos()) != 0
+    }
+
+    /// Returns whether the set contains no squares
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the number of squares in the set
+    pub fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Add a square to the set
+    pub fn set(&mut self, pos: Position) {
+        self.0 |= 1 << pos.pos();
+    }
+
+    /// Remove a square from the set
+    pub fn clear(&mut self, pos: Position) {
+        self.0 &= !(1 << pos.pos());
+    }
+
+    /// Returns the lowest square in the set, starting from A1
+    pub fn first(self) -> Option<Position> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(Position::from(self.0.trailing_zeros() as i8))
+        }
+    }
+
+    /// The squares on the given file (column, 0-7)
+    pub const fn file(col: i8) -> Self {
+        Bitboard(0x0101_0101_0101_0101 << col)
+    }
+
+    /// The squares on the given rank (row, 0-7)
+    pub const fn rank(row: i8) -> Self {
+        Bitboard(0xff << (8 * row))
+    }
+
+    /// The squares on the files either side of the given file (column, 0-7)
+    pub const fn adjacent_files(col: i8) -> Self {
+        let file = Self::file(col).0;
+        Bitboard(((file << 1) & !Self::file(0).0) | ((file >> 1) & !Self::file(7).0))
+    }
+
+    /// The squares on the diagonal running in the direction of A1 to H8
+    /// through the given square
+    pub fn diagonal(through: Position) -> Self {
+        const A1_H8: u64 = 0x8040_2010_0804_0201;
+        let rows = through.row() - through.col();
+        if rows >= 0 {
+            Bitboard(A1_H8 << (8 * rows))
+        } else {
+            Bitboard(A1_H8 >> (-8 * rows))
+        }
+    }
+
+    /// The squares on the diagonal running in the direction of A8 to H1
+    /// through the given square
+    pub fn anti_diagonal(through: Position) -> Self {
+        const H1_A8: u64 = 0x0102_0408_1020_4080;
+        let rows = through.row() + through.col() - 7;
+        if rows >= 0 {
+            Bitboard(H1_A8 << (8 * rows))
+        } else {
+            Bitboard(H1_A8 >> (-8 * rows))
+        }
+    }
+
+    /// The squares in front of the given square on its file, from the
+    /// point of view of the given color
+    pub fn forward_span(from: Position, color: Color) -> Self {
+        let file = Self::file(from.col()).0;
+        match color {
+            Color::White if from.row() == 7 => Self::EMPTY,
+            Color::White => Bitboard(file & (u64::MAX << (8 * (from.row() + 1)))),
+            Color::Black => Bitboard(file & ((1 << (8 * from.row())) - 1)),
+        }
+    }
+}
+
+impl Iterator for Bitboard {
+    type Item = Position;
+
+    /// Remove and return the lowest square in the set
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.first()?;
+        self.0 &= self.0 - 1;
+        Some(pos)
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Bitboard;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 ^ rhs.0)
+    }
+}
+
+impl BitXorAssign for Bitboard {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Self::Output {
+        Bitboard(!self.0)
+    }
+}
+
+impl Display for Bitboard {
+    /// Draw the set as a grid, with the 8th rank at the top
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for row in (0..8).rev() {
+            for col in 0..8 {
+                if self.contains(Position::new(row, col)) {
+                    write!(f, "x")?;
+                } else {
+                    write!(f, ".")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Squares attacked by a piece that jumps by the given offsets, from each
+/// square
+const fn leaper_attacks<const N: usize>(offsets: [(i8, i8); N]) -> [Bitboard; 64] {
+    let mut attacks = [Bitboard::EMPTY; 64];
+    let mut square = 0;
+    while square < 64 {
+        let (row, col) = (square as i8 / 8, square as i8 % 8);
+        let mut bits = 0;
+        let mut i = 0;
+        while i < N {
+            let (r, c) = (row + offsets[i].0, col + offsets[i].1);
+            if r >= 0 && r < 8 && c >= 0 && c < 8 {
+                bits |= 1 << (r * 8 + c);
+            }
+            i += 1;
+        }
+        attacks[square] = Bitboard(bits);
+        square += 1;
+    }
+    attacks
+}
+
+const KNIGHT_ATTACKS: [Bitboard; 64] = leaper_attacks(KNIGHT_MOVES);
+
+const KING_ATTACKS: [Bitboard; 64] = leaper_attacks([
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+]);
+
+/// Pawn attacks, indexed by color
+const PAWN_ATTACKS: [[Bitboard; 64]; 2] = [
+    leaper_attacks([(1, -1), (1, 1)]),
+    leaper_attacks([(-1, -1), (-1, 1)]),
+];
+
+/// Returns the squares attacked by a knight on the given square
+pub fn knight_attacks(pos: Position) -> Bitboard {
+    KNIGHT_ATTACKS[pos.pos()]
+}
+
+/// Returns the squares attacked by a king on the given square
+pub fn king_attacks(pos: Position) -> Bitboard {
+    KING_ATTACKS[pos.pos()]
+}
+
+/// Returns the squares attacked by a pawn of the given color on the given
+/// square
+pub fn pawn_attacks(color: Color, pos: Position) -> Bitboard {
+    PAWN_ATTACKS[color.index()][pos.pos()]
+}
+
+/// Returns the squares attacked by a rook on the given square, where
+/// `occupied` contains the pieces that block it
+#[cfg(not(feature = "std"))]
+pub fn rook_attacks(pos: Position, occupied: Bitboard) -> Bitboard {
+    ray_attacks(pos, occupied, ROOK_DIRECTIONS)
+}
+
+/// Returns the squares attacked by a bishop on the given square, where
+/// `occupied` contains the pieces that block it
+#[cfg(not(feature = "std"))]
+pub fn bishop_attacks(pos: Position, occupied: Bitboard) -> Bitboard {
+    ray_attacks(pos, occupied, BISHOP_DIRECTIONS)
+}
+
+/// Returns the squares attacked along the given directions, up to and
+/// including the first occupied square in each.
+///
+/// This is slow, so is only used to build the lookup tables for
+/// `rook_attacks` and `bishop_attacks`, unless they aren't available
+/// without the `std` feature.
+pub(super) fn ray_attacks(
+    pos: Position,
+    occupied: Bitboard,
+    directions: [(i8, i8); 4],
+) -> Bitboard {
+    let mut attacks = Bitboard::EMPTY;
+    for (r, c) in directions {
+        let mut square = pos;
+        while let Some(next) = square.offset(r, c) {
+            square = next;
+            attacks.set(square);
+            if occupied.contains(square) {
+                break;
+            }
+        }
+    }
+    attacks
+}
+
+/// Squares on the line through each pair of squares, from one edge of the
+/// board to the other, if they share a row, column or diagonal. Indexed as
+/// `[from][to]`, and `between` is the part strictly between the two.
+struct Lines {
+    line: [[Bitboard; 64]; 64],
+    between: [[Bitboard; 64]; 64],
+}
+
+const fn build_lines() -> Lines {
+    let mut lines = Lines {
+        line: [[Bitboard::EMPTY; 64]; 64],
+        between: [[Bitboard::EMPTY; 64]; 64],
+    };
+    let mut a = 0;
+    while a < 64 {
+        let (row, col) = (a as i8 / 8, a as i8 % 8);
+        let directions = [
+            (1, 0),
+            (0, 1),
+            (1, 1),
+            (1, -1),
+            (-1, 0),
+            (0, -1),
+            (-1, -1),
+            (-1, 1),
+        ];
+        let mut d = 0;
+        while d < 8 {
+            let (dr, dc) = directions[d];
+            // The whole line through `a` in this direction and its opposite
+            let mut full = 1 << a;
+            let mut step = 1;
+            while step < 8 {
+                let (r, c) = (row + dr * step, col + dc * step);
+                if r >= 0 && r < 8 && c >= 0 && c < 8 {
+                    full |= 1 << (r * 8 + c);
+                }
+                let (r, c) = (row - dr * step, col - dc * step);
+                if r >= 0 && r < 8 && c >= 0 && c < 8 {
+                    full |= 1 << (r * 8 + c);
+                }
+                step += 1;
+            }
+            // Every square reached by walking in this direction
+            let mut path = 0;
+            let (mut r, mut c) = (row + dr, col + dc);
+            while r >= 0 && r < 8 && c >= 0 && c < 8 {
+                let b = (r * 8 + c) as usize;
+                lines.line[a][b] = Bitboard(full);
+                lines.between[a][b] = Bitboard(path);
+                path |= 1 << b;
+                r += dr;
+                c += dc;
+            }
+            d += 1;
+        }
+        a += 1;
+    }
+    lines
+}
+
+static LINES: Lines = build_lines();
+
+/// Returns the squares strictly between the two given squares, or no squares
+/// if they aren't on the same row, column or diagonal
+pub fn between(a: Position, b: Position) -> Bitboard {
+    LINES.between[a.pos()][b.pos()]
+}
+
+/// Returns every square on the row, column or diagonal through both of the
+/// given squares, including them, or no squares if they aren't aligned
+pub fn line(a: Position, b: Position) -> Bitboard {
+    LINES.line[a.pos()][b.pos()]
+}
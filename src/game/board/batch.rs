@@ -0,0 +1,93 @@
+use crate::game::{bitboard::Bitboard, Color, PieceType};
+use alloc::vec::Vec;
+
+use super::Board;
+
+/// Number of planes stored for each position in a `PositionBatch`
+pub const BATCH_PLANES: usize = 12;
+
+/// Many positions laid out one after another in a single buffer, so that
+/// they can be evaluated together, such as by a neural network.
+///
+/// Each position is stored as `BATCH_PLANES` bitboards, one for each kind
+/// and color of piece, at `color.index() * 6 + kind.index()`. The player
+/// whose turn it is in each position is stored separately.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PositionBatch {
+    planes: Vec<Bitboard>,
+    whose_turn: Vec<Color>,
+}
+
+impl PositionBatch {
+    /// Create an empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty batch with room for the given number of positions
+    pub fn with_capacity(positions: usize) -> Self {
+        Self {
+            planes: Vec::with_capacity(positions * BATCH_PLANES),
+            whose_turn: Vec::with_capacity(positions),
+        }
+    }
+
+    /// Add the current position of the given board to the end of the batch
+    pub fn push(&mut self, board: &Board) {
+        for color in [Color::White, Color::Black] {
+            for plane in board.pieces {
+                self.planes.push(plane & board.colors[color.index()]);
+            }
+        }
+        self.whose_turn.push(board.whose_turn);
+    }
+
+    /// Remove every position, keeping the buffer for reuse
+    pub fn clear(&mut self) {
+        self.planes.clear();
+        self.whose_turn.clear();
+    }
+
+    /// Returns the number of positions in the batch
+    pub fn len(&self) -> usize {
+        self.whose_turn.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.whose_turn.is_empty()
+    }
+
+    /// Returns the planes of every position, one position after another
+    pub fn planes(&self) -> &[Bitboard] {
+        &self.planes
+    }
+
+    /// Returns the planes of the position at the given index
+    pub fn position(&self, index: usize) -> &[Bitboard] {
+        &self.planes[index * BATCH_PLANES..(index + 1) * BATCH_PLANES]
+    }
+
+    /// Returns the squares containing pieces of the given kind and color in
+    /// the position at the given index
+    pub fn pieces(&self, index: usize, kind: PieceType, color: Color) -> Bitboard {
+        self.position(index)[color.index() * 6 + kind.index()]
+    }
+
+    /// Returns the player whose turn it is in the position at the given
+    /// index
+    pub fn whose_turn(&self, index: usize) -> Color {
+        self.whose_turn[index]
+    }
+}
+
+impl Board {
+    /// Lay out the current positions of the given boards in one buffer, for
+    /// evaluating them together
+    pub fn batch<'a>(boards: impl IntoIterator<Item = &'a Board>) -> PositionBatch {
+        let mut batch = PositionBatch::new();
+        for board in boards {
+            batch.push(board);
+        }
+        batch
+    }
+}
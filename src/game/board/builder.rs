@@ -0,0 +1,102 @@
+use crate::game::{piece::Piece, CastlingRights, Color, PieceType, Position};
+use alloc::{vec, vec::Vec};
+
+use super::{Board, PositionProblem};
+
+/// Builds a board in a custom position, as an alternative to writing a FEN
+/// string. The position is validated when it is built.
+#[derive(Debug, Clone)]
+pub struct BoardBuilder {
+    squares: [Option<(PieceType, Color)>; 64],
+    whose_turn: Color,
+    castling_rights: CastlingRights,
+    en_passant_target: Option<Position>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+}
+
+impl Default for BoardBuilder {
+    fn default() -> Self {
+        Self {
+            squares: [None; 64],
+            whose_turn: Color::White,
+            castling_rights: CastlingRights::default(),
+            en_passant_target: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+        }
+    }
+}
+
+impl BoardBuilder {
+    /// Start building an empty board, with white to move and no castling
+    /// rights
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Place a piece at the given position, replacing any piece already
+    /// there
+    pub fn piece(mut self, position: Position, kind: PieceType, color: Color) -> Self {
+        self.squares[position.pos()] = Some((kind, color));
+        self
+    }
+
+    /// Set whose turn it is
+    pub fn side_to_move(mut self, color: Color) -> Self {
+        self.whose_turn = color;
+        self
+    }
+
+    /// Set which castling moves are allowed
+    pub fn castling(mut self, rights: CastlingRights) -> Self {
+        self.castling_rights = rights;
+        self
+    }
+
+    /// Set the square that can be moved to in order to capture en passant
+    pub fn en_passant(mut self, target: Option<Position>) -> Self {
+        self.en_passant_target = target;
+        self
+    }
+
+    /// Set the number of half moves since the last capture or pawn push
+    pub fn halfmove_clock(mut self, clock: u32) -> Self {
+        self.halfmove_clock = clock;
+        self
+    }
+
+    /// Set the number of the current full move, which starts at 1
+    pub fn fullmove_number(mut self, number: u32) -> Self {
+        self.fullmove_number = number;
+        self
+    }
+
+    /// Build the board, or return the problems that mean the position
+    /// couldn't occur in a legal game
+    pub fn build(self) -> Result<Board, Vec<PositionProblem>> {
+        let mut board = Board {
+            whose_turn: self.whose_turn,
+            castling_rights: vec![self.castling_rights],
+            en_passant_target: self.en_passant_target,
+            // Counters too large to store are capped, rather than wrapping
+            // around to negative numbers
+            half_move_clock: vec![i32::try_from(self.halfmove_clock).unwrap_or(i32::MAX)],
+            num_moves: i32::try_from(self.fullmove_number).unwrap_or(i32::MAX),
+            ..Default::default()
+        };
+        for (i, square) in self.squares.into_iter().enumerate() {
+            if let Some((kind, color)) = square {
+                board.put_piece(Position::from(i as i8), Piece::new(kind, color));
+            }
+        }
+        board.refresh_position_hash();
+
+        let problems = board.validate_position();
+        if problems.is_empty() {
+            Ok(board)
+        } else {
+            Err(problems)
+        }
+    }
+}
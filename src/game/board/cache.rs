@@ -0,0 +1,26 @@
+#[cfg(not(feature = "std"))]
+use core::cell::OnceCell as OnceLock;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+use crate::game::GameState;
+
+/// Answers to queries about the current position, which are worked out the
+/// first time they are asked for, and forgotten whenever the position
+/// changes, so that asking repeatedly in one position is free.
+///
+/// Without the `std` feature there is no `OnceLock`, so a `OnceCell` is
+/// used instead, which means the board can't be shared between threads.
+#[derive(Debug, Clone, Default)]
+pub(super) struct PlyCache {
+    pub is_check: OnceLock<bool>,
+    pub has_legal_move: OnceLock<bool>,
+    pub game_state: OnceLock<GameState>,
+}
+
+impl PlyCache {
+    /// Forget everything, after the position changed
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
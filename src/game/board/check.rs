@@ -0,0 +1,65 @@
+use crate::game::Turn;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::{Board, FenError};
+
+/// The result of checking a candidate move with `check_moves`
+#[derive(Debug, Clone)]
+pub struct MoveCheck {
+    /// The candidate move, as it was given
+    pub input: String,
+
+    /// The legal turn the candidate move describes, or `None` if it is
+    /// illegal or couldn't be parsed
+    pub turn: Option<Turn>,
+
+    /// FEN string of the position after the move, if it is legal
+    pub fen: Option<String>,
+}
+
+impl MoveCheck {
+    /// Returns whether the candidate move is legal
+    pub fn is_legal(&self) -> bool {
+        self.turn.is_some()
+    }
+}
+
+impl Board {
+    /// Check whether each of the given candidate moves is legal in the
+    /// current position, giving the resulting position for each legal one.
+    ///
+    /// Moves may be given in either UCI or SAN notation. Each move is checked
+    /// from the current position, which is unchanged afterwards.
+    pub fn check_moves<S: AsRef<str>>(&mut self, moves: &[S]) -> Vec<MoveCheck> {
+        moves
+            .iter()
+            .map(|input| {
+                let input = input.as_ref();
+                let turn = self.parse_uci(input).or_else(|| self.parse_san(input));
+                let fen = turn.map(|turn| {
+                    self.make_turn(turn);
+                    let fen = self.to_fen();
+                    self.undo_turn();
+                    fen
+                });
+                MoveCheck {
+                    input: input.to_string(),
+                    turn,
+                    fen,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Check whether each of the given candidate moves is legal in the position
+/// given by a FEN string, giving the resulting position for each legal one.
+///
+/// This only sets up the position once, so is much cheaper than checking
+/// each move separately. Moves may be given in either UCI or SAN notation.
+pub fn check_moves<S: AsRef<str>>(fen: &str, moves: &[S]) -> Result<Vec<MoveCheck>, FenError> {
+    Ok(Board::from_fen(fen)?.check_moves(moves))
+}
@@ -0,0 +1,97 @@
+use core::{error::Error, fmt::Display};
+
+use crate::game::{DrawReason, GameState, Turn};
+
+use super::Board;
+
+/// Reason a draw claimed by a player was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimError {
+    /// The game is already over
+    GameOver,
+
+    /// The turn the player announced isn't legal
+    IllegalTurn,
+
+    /// The turn the player announced ends the game by itself, such as by
+    /// checkmate, so should be made instead of claiming a draw
+    EndsGame,
+
+    /// Neither the 50 move rule nor threefold repetition applies to the
+    /// position the claim was about
+    /// Includes the number of half moves since the last capture or pawn
+    /// move, and the number of times the position has occurred
+    NoDraw {
+        halfmove_clock: u32,
+        repetitions: usize,
+    },
+}
+
+impl Display for ClaimError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ClaimError::GameOver => write!(f, "the game is over"),
+            ClaimError::IllegalTurn => write!(f, "the announced move isn't legal"),
+            ClaimError::EndsGame => write!(f, "the announced move ends the game by itself"),
+            ClaimError::NoDraw {
+                halfmove_clock,
+                repetitions,
+            } => write!(
+                f,
+                "the position has occurred {} times, with {} half moves since the last capture \
+                 or pawn move",
+                repetitions, halfmove_clock
+            ),
+        }
+    }
+}
+
+impl Error for ClaimError {}
+
+impl Board {
+    /// Claim a draw for the player to move by threefold repetition or the 50
+    /// move rule, as a player would ask the arbiter to.
+    ///
+    /// The claim can be about the current position, or about the position
+    /// after a turn the player announces they will make, which is made if
+    /// the claim is correct. A correct claim ends the game as a draw, which
+    /// is kept when turns are undone, like a resignation, and the reason for
+    /// the draw is returned. An incorrect claim leaves the board as it was.
+    /// Under FIDE rules, the announced turn must still be made, which is
+    /// left to the caller.
+    ///
+    /// With the default `Rules`, these draws happen by themselves, so claims
+    /// are only needed with higher limits, such as `Rules::without_claims`.
+    pub fn claim_draw(&mut self, turn: Option<Turn>) -> Result<DrawReason, ClaimError> {
+        if self.is_game_over() {
+            return Err(ClaimError::GameOver);
+        }
+        if let Some(turn) = turn {
+            if !self.get_moves().contains(&turn) {
+                return Err(ClaimError::IllegalTurn);
+            }
+            self.make_turn(turn);
+            if self.is_game_over() {
+                self.undo_turn();
+                return Err(ClaimError::EndsGame);
+            }
+        }
+        let reason = if self.is_threefold_repetition() {
+            DrawReason::ThreefoldRepetition
+        } else if self.is_50_move_rule() {
+            DrawReason::FiftyMoveRule
+        } else {
+            let error = ClaimError::NoDraw {
+                halfmove_clock: self.halfmove_clock(),
+                repetitions: self.repetition_count(),
+            };
+            if turn.is_some() {
+                self.undo_turn();
+            }
+            return Err(error);
+        };
+        self.end_by_players(GameState::Draw(reason.clone()))
+            .expect("The game isn't over");
+        Ok(reason)
+    }
+}
@@ -0,0 +1,251 @@
+use core::{error::Error, fmt::Display};
+
+use crate::game::{piece::PROMOTABLE_TYPES, CastlingSide, GameState, PieceType, Position, Turn};
+
+use super::Board;
+
+/// The reason that moving a piece from one square to another isn't legal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IllegalMoveReason {
+    /// The game is over, so no more turns can be made
+    GameOver,
+
+    /// There is no piece at the position being moved from
+    NoPiece(Position),
+
+    /// The piece at the position being moved from belongs to the player
+    /// whose turn it isn't
+    NotYourPiece(Position),
+
+    /// The piece at the destination belongs to the player moving
+    CaptureOwnPiece(Position),
+
+    /// The kind of piece being moved can't move that way
+    InvalidMovement(PieceType),
+
+    /// A piece at the given position is in the way
+    Blocked(Position),
+
+    /// A pawn is moving diagonally, but there is nothing to capture
+    NothingToCapture(Position),
+
+    /// The move would leave the player's king in check
+    LeavesKingInCheck,
+
+    /// The player no longer has the right to castle on the given side
+    CastlingNotAllowed(CastlingSide),
+
+    /// The king can't castle while it is in check
+    CastlingOutOfCheck,
+
+    /// The king can't castle through the given square, as it is attacked
+    CastlingThroughCheck(Position),
+
+    /// A pawn reaching the last rank must be promoted
+    MissingPromotion,
+
+    /// Only a pawn reaching the last rank can be promoted
+    PromotionNotAllowed,
+
+    /// A pawn can't be promoted to the given kind of piece
+    InvalidPromotion(PieceType),
+}
+
+impl Display for IllegalMoveReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            IllegalMoveReason::GameOver => write!(f, "the game is over"),
+            IllegalMoveReason::NoPiece(pos) => write!(f, "there is no piece at {}", pos),
+            IllegalMoveReason::NotYourPiece(pos) => {
+                write!(f, "the piece at {} belongs to the other player", pos)
+            }
+            IllegalMoveReason::CaptureOwnPiece(pos) => {
+                write!(f, "the piece at {} can't be captured by its own side", pos)
+            }
+            IllegalMoveReason::InvalidMovement(kind) => {
+                write!(f, "a {} can't move like that", kind)
+            }
+            IllegalMoveReason::Blocked(pos) => write!(f, "the piece at {} is in the way", pos),
+            IllegalMoveReason::NothingToCapture(pos) => {
+                write!(f, "a pawn can only move to {} by capturing", pos)
+            }
+            IllegalMoveReason::LeavesKingInCheck => write!(f, "the king would be in check"),
+            IllegalMoveReason::CastlingNotAllowed(side) => write!(
+                f,
+                "castling {} isn't allowed",
+                match side {
+                    CastlingSide::KingSide => "king side",
+                    CastlingSide::QueenSide => "queen side",
+                }
+            ),
+            IllegalMoveReason::CastlingOutOfCheck => {
+                write!(f, "the king can't castle out of check")
+            }
+            IllegalMoveReason::CastlingThroughCheck(pos) => {
+                write!(
+                    f,
+                    "the king can't castle through {}, as it is attacked",
+                    pos
+                )
+            }
+            IllegalMoveReason::MissingPromotion => {
+                write!(f, "a pawn reaching the last rank must promote")
+            }
+            IllegalMoveReason::PromotionNotAllowed => {
+                write!(f, "only a pawn reaching the last rank can promote")
+            }
+            IllegalMoveReason::InvalidPromotion(kind) => {
+                write!(f, "a pawn can't promote to a {}", kind)
+            }
+        }
+    }
+}
+
+impl Error for IllegalMoveReason {}
+
+impl Board {
+    /// Find the legal turn that moves the piece at `from` to `to`, as with
+    /// `find_move`, or explain why there isn't one.
+    ///
+    /// This is intended for user interfaces and teaching tools that want to
+    /// show why a move was rejected.
+    pub fn diagnose_move(
+        &mut self,
+        from: Position,
+        to: Position,
+        promote_to: Option<PieceType>,
+    ) -> Result<Turn, IllegalMoveReason> {
+        if self.get_game_state() != GameState::Playing {
+            return Err(IllegalMoveReason::GameOver);
+        }
+        let piece = *self
+            .at_position(from)
+            .ok_or(IllegalMoveReason::NoPiece(from))?;
+        if piece.color != self.whose_turn {
+            return Err(IllegalMoveReason::NotYourPiece(from));
+        }
+
+        if piece.kind == PieceType::King {
+            if let Some(side) = self.castling_side(from, to) {
+                if promote_to.is_some() {
+                    return Err(IllegalMoveReason::PromotionNotAllowed);
+                }
+                let turn = self.castling_turn(from, side)?;
+                return self.find_legal(turn);
+            }
+        }
+
+        if let Some(other) = self.at_position(to) {
+            if other.color == piece.color {
+                return Err(IllegalMoveReason::CaptureOwnPiece(to));
+            }
+        }
+
+        if piece.kind == PieceType::Pawn {
+            self.diagnose_pawn_move(from, to, promote_to)?;
+        } else {
+            if promote_to.is_some() {
+                return Err(IllegalMoveReason::PromotionNotAllowed);
+            }
+            if !piece.could_move_to(from, to, self) {
+                return Err(IllegalMoveReason::InvalidMovement(piece.kind));
+            }
+            if piece.kind != PieceType::Knight {
+                self.diagnose_path(from, to)?;
+            }
+        }
+
+        self.find_move(from, to, promote_to)
+            .ok_or(IllegalMoveReason::LeavesKingInCheck)
+    }
+
+    /// Returns the side the king at `from` is castling towards, if moving it
+    /// to `to` is an attempt to castle, either by moving it to its
+    /// destination, or onto its own rook
+    fn castling_side(&self, from: Position, to: Position) -> Option<CastlingSide> {
+        let color = self.whose_turn;
+        if from.row() != color.get_home() || to.row() != from.row() {
+            return None;
+        }
+        let side = if to.col() > from.col() {
+            CastlingSide::KingSide
+        } else {
+            CastlingSide::QueenSide
+        };
+        let onto_rook = matches!(
+            self.at_position(to),
+            Some(piece) if piece.kind == PieceType::Rook && piece.color == color
+        );
+        let to_destination =
+            (to.col() - from.col()).abs() >= 2 && to.col() == side.king_destination();
+        (onto_rook || to_destination).then_some(side)
+    }
+
+    /// Returns the given turn if it doesn't leave the king in check
+    fn find_legal(&mut self, turn: Turn) -> Result<Turn, IllegalMoveReason> {
+        if self.is_move_legal(&turn) {
+            Ok(turn)
+        } else {
+            Err(IllegalMoveReason::LeavesKingInCheck)
+        }
+    }
+
+    /// Check that the squares between `from` and `to`, which must be on the
+    /// same line, are empty
+    fn diagnose_path(&self, from: Position, to: Position) -> Result<(), IllegalMoveReason> {
+        let (r, c) = (
+            (to.row() - from.row()).signum(),
+            (to.col() - from.col()).signum(),
+        );
+        let mut pos = from.offset(r, c).unwrap();
+        while pos != to {
+            if self.at_position(pos).is_some() {
+                return Err(IllegalMoveReason::Blocked(pos));
+            }
+            pos = pos.offset(r, c).unwrap();
+        }
+        Ok(())
+    }
+
+    /// Check the movement and promotion of a pawn
+    fn diagnose_pawn_move(
+        &self,
+        from: Position,
+        to: Position,
+        promote_to: Option<PieceType>,
+    ) -> Result<(), IllegalMoveReason> {
+        let color = self.whose_turn;
+        let direction = color.get_direction();
+        let row_diff = to.row() - from.row();
+        let col_diff = (to.col() - from.col()).abs();
+        let invalid = Err(IllegalMoveReason::InvalidMovement(PieceType::Pawn));
+
+        if col_diff == 0 {
+            let first_move = from.row() == color.get_home() + direction;
+            if row_diff != direction && !(first_move && row_diff == 2 * direction) {
+                return invalid;
+            }
+            self.diagnose_path(from, to)?;
+            if self.at_position(to).is_some() {
+                return Err(IllegalMoveReason::Blocked(to));
+            }
+        } else if col_diff == 1 && row_diff == direction {
+            if self.at_position(to).is_none() && self.en_passant_target != Some(to) {
+                return Err(IllegalMoveReason::NothingToCapture(to));
+            }
+        } else {
+            return invalid;
+        }
+
+        match promote_to {
+            None if to.row() == (!color).get_home() => Err(IllegalMoveReason::MissingPromotion),
+            Some(_) if to.row() != (!color).get_home() => {
+                Err(IllegalMoveReason::PromotionNotAllowed)
+            }
+            Some(kind) if !PROMOTABLE_TYPES.contains(&kind) => {
+                Err(IllegalMoveReason::InvalidPromotion(kind))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::game::{piece::Piece, CastlingSide, Color, PieceType, Position};
+use alloc::vec;
+
+use super::Board;
+
+impl Board {
+    /// Place a piece at the given position, returning the kind and color of
+    /// the piece it replaced, if any.
+    ///
+    /// Editing the board starts a new game from the edited position, so the
+    /// move history is cleared. Castling rights and the en passant target
+    /// are removed if they are no longer possible.
+    pub fn set_piece(
+        &mut self,
+        position: Position,
+        kind: PieceType,
+        color: Color,
+    ) -> Option<(PieceType, Color)> {
+        let replaced = self.take_piece(position);
+        self.put_piece(position, Piece::new(kind, color));
+        self.after_edit();
+        replaced.map(|piece| (piece.kind, piece.color))
+    }
+
+    /// Remove the piece at the given position, returning its kind and color,
+    /// if there was one.
+    ///
+    /// As with `set_piece`, this starts a new game from the edited position.
+    pub fn remove_piece(&mut self, position: Position) -> Option<(PieceType, Color)> {
+        let removed = self.take_piece(position);
+        self.after_edit();
+        removed.map(|piece| (piece.kind, piece.color))
+    }
+
+    /// Remove every piece from the board, along with all castling rights,
+    /// the en passant target and the move history, leaving white to move.
+    ///
+    /// A player without a king is never in check and has no legal moves, so
+    /// `validate_position` should be used to check that both kings have
+    /// been placed before the position is played from.
+    pub fn clear(&mut self) {
+        *self = Self {
+            rules: self.rules,
+            stats: self.stats.take(),
+            ..Default::default()
+        };
+        self.refresh_position_hash();
+    }
+
+    /// Make the edited position the start of a new game, and remove any
+    /// castling rights or en passant target that it no longer allows
+    fn after_edit(&mut self) {
+        let mut rights = self.castling_rights();
+        for color in [Color::White, Color::Black] {
+            for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+                if let Some(col) = rights.rook_col(color, side) {
+                    if !self.is_castling_valid(color, side, col) {
+                        rights.set(color, side, None);
+                    }
+                }
+            }
+        }
+        if self
+            .en_passant_target
+            .is_some_and(|target| !self.is_en_passant_valid(target))
+        {
+            self.en_passant_target = None;
+        }
+
+        self.captures.clear();
+        self.moves.clear();
+        self.null_moves.clear();
+        self.en_passant_history.clear();
+        self.ended_by_players = None;
+        self.castling_rights = vec![rights];
+        self.half_move_clock = vec![self.halfmove_clock() as i32];
+        self.position_hashes.clear();
+        self.irreversible_plies.clear();
+        self.refresh_position_hash();
+    }
+}
@@ -1,6 +1,12 @@
-use std::num::ParseIntError;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{error::Error, fmt::Display, num::ParseIntError, str::FromStr};
 
-use crate::game::{piece::Piece, Color, PieceType, Position};
+use crate::game::{piece::Piece, CastlingRights, CastlingSide, Color, PieceType, Position};
 
 use super::Board;
 
@@ -49,12 +55,61 @@ pub enum FenError {
     InvalidNumber(ParseIntError),
 }
 
+impl Display for FenError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FenError::NotAscii => write!(f, "FEN string contains non-ASCII characters"),
+            FenError::IncorrectSections(n) => {
+                write!(f, "FEN string has {} sections, expected 6", n)
+            }
+            FenError::IncorrectRows(n) => write!(f, "FEN board has {} rows, expected 8", n),
+            FenError::IncorrectCols(row, col) => write!(
+                f,
+                "FEN board has {} columns in rank {}, expected 8",
+                col,
+                row + 1
+            ),
+            FenError::InvalidPiece(c) => write!(f, "invalid piece '{}' in FEN board", c),
+            FenError::InvalidColor(color) => {
+                write!(f, "invalid color to move '{}', expected 'w' or 'b'", color)
+            }
+            FenError::InvalidPosition(pos) => write!(f, "invalid square '{}'", pos),
+            FenError::InvalidCastling(castling) => {
+                write!(f, "invalid castling rights '{}'", castling)
+            }
+            FenError::IllegalCastling(castling) => write!(
+                f,
+                "castling rights '{}' don't match the positions of the kings and rooks",
+                castling
+            ),
+            FenError::InvalidNumber(e) => write!(f, "invalid move counter: {}", e),
+        }
+    }
+}
+
+impl Error for FenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FenError::InvalidNumber(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 impl From<ParseIntError> for FenError {
     fn from(e: ParseIntError) -> Self {
         FenError::InvalidNumber(e)
     }
 }
 
+impl FromStr for Board {
+    type Err = FenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Board::from_fen(s)
+    }
+}
+
 impl Board {
     /// Create a new board from a FEN string
     pub fn from_fen(fen: &str) -> Result<Self, FenError> {
@@ -78,8 +133,8 @@ impl Board {
         let to_move = fen_split[1];
         let castling = fen_split[2];
         let en_passant_target = fen_split[3];
-        board.half_move_clock = vec![fen_split[4].parse()?];
-        board.num_moves = fen_split[5].parse()?;
+        board.half_move_clock = vec![parse_counter(fen_split[4])?];
+        board.num_moves = parse_counter(fen_split[5])?;
 
         // Piece positions
         for c in positions.chars() {
@@ -96,12 +151,12 @@ impl Board {
                 if col != 8 {
                     return Err(FenError::IncorrectCols(row, col));
                 }
-                row += 1;
-                col = 0;
                 // Too many rows, invalid FEN
-                if row == 8 {
-                    return Err(FenError::IncorrectRows(row));
+                if row == 0 {
+                    return Err(FenError::IncorrectRows(9));
                 }
+                row -= 1;
+                col = 0;
             } else {
                 // If we're >= col 8, there were too many columns
                 if col >= 8 {
@@ -118,56 +173,229 @@ impl Board {
                     'b' => PieceType::Bishop,
                     'n' => PieceType::Knight,
                     'r' => PieceType::Rook,
+                    'p' => PieceType::Pawn,
                     _ => return Err(FenError::InvalidPiece(c)),
                 };
                 // Add piece to the board
-                board.squares[Position::new(row, col).pos()] = Some(Piece::new(kind, color));
+                board.put_piece(Position::new(row, col), Piece::new(kind, color));
+                col += 1;
             }
         }
-        // Afterwards, we should have completed 7 rows
-        if row != 7 {
-            return Err(FenError::IncorrectRows(row));
+        // Afterwards, we should have completed all 8 rows
+        if row != 0 {
+            return Err(FenError::IncorrectRows(8 - row));
+        }
+        if col != 8 {
+            return Err(FenError::IncorrectCols(row, col));
         }
 
-        // Castling logic
+        board.parse_castling(castling)?;
 
-        // Disable castling by default, then enable it if required
-        for (pos, color) in [
-            (Position::new(0, 0), Color::White),
-            (Position::new(0, 7), Color::White),
-            (Position::new(7, 0), Color::Black),
-            (Position::new(7, 7), Color::Black),
-        ] {
-            if let Some(piece) = &mut board.squares[pos.pos()] {
-                if piece.kind == PieceType::Rook && piece.color == color {
-                    piece.move_count = 1;
-                }
+        // Parse other info
+        board.whose_turn = Color::from_fen(to_move)?;
+        board.en_passant_target = Position::from_fen(en_passant_target)?;
+        board.refresh_position_hash();
+
+        Ok(board)
+    }
+
+    /// Create a new board from an EPD record.
+    ///
+    /// EPD is a FEN string without the move counters, followed by any
+    /// number of `;`-terminated operations, such as `bm Nf3; id "test";`.
+    /// The move counters are taken from the `hmvc` and `fmvn` operations if
+    /// they are given, and other operations are ignored.
+    pub fn from_epd(epd: &str) -> Result<Self, FenError> {
+        let fields: Vec<&str> = epd.split_ascii_whitespace().take(4).collect();
+        if fields.len() != 4 {
+            return Err(FenError::IncorrectSections(fields.len()));
+        }
+        let mut half_move_clock = "0";
+        let mut num_moves = "1";
+        let operations = epd
+            .split_ascii_whitespace()
+            .skip(4)
+            .collect::<Vec<&str>>()
+            .join(" ");
+        for operation in operations.split(';') {
+            let mut parts = operation.split_ascii_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some("hmvc"), Some(n)) => half_move_clock = n,
+                (Some("fmvn"), Some(n)) => num_moves = n,
+                _ => {}
             }
         }
-        // If some squares can castle
+        let fen = format!("{} {} {}", fields.join(" "), half_move_clock, num_moves);
+        Self::from_fen(&fen)
+    }
+
+    /// Set the castling rights from the castling section of a FEN string.
+    ///
+    /// As well as the standard `KQkq`, this accepts Shredder-FEN style file
+    /// letters (eg `HAha`), where each letter gives the file of a rook that
+    /// can still castle. As with X-FEN, `K` and `Q` refer to the outermost
+    /// rook on that side of the king, so Chess960 positions can be loaded
+    /// using either style.
+    fn parse_castling(&mut self, castling: &str) -> Result<(), FenError> {
+        let mut rights = CastlingRights::default();
         if castling != "-" {
             for c in castling.chars() {
-                let (pos, color) = match c {
-                    'Q' => (Position::new(0, 0), Color::White),
-                    'K' => (Position::new(0, 7), Color::White),
-                    'q' => (Position::new(7, 0), Color::Black),
-                    'k' => (Position::new(7, 7), Color::Black),
-                    _ => return Err(FenError::IllegalCastling(castling.to_string())),
+                let color = if c.is_ascii_uppercase() {
+                    Color::White
+                } else {
+                    Color::Black
                 };
-                // If the correct rook is there
-                if let Some(piece) = &mut board.squares[pos.pos()] {
-                    if piece.kind == PieceType::Rook && piece.color == color {
-                        // Let it castle
-                        piece.move_count = 0;
+                let illegal = || FenError::IllegalCastling(castling.to_string());
+                let king_col = self.home_king_col(color).ok_or_else(illegal)?;
+                let (side, col) = match c.to_ascii_lowercase() {
+                    'k' => (
+                        CastlingSide::KingSide,
+                        self.outermost_castling_rook(color, CastlingSide::KingSide),
+                    ),
+                    'q' => (
+                        CastlingSide::QueenSide,
+                        self.outermost_castling_rook(color, CastlingSide::QueenSide),
+                    ),
+                    'a'..='h' => {
+                        let col = (c.to_ascii_lowercase() as u8 - b'a') as i8;
+                        if col > king_col {
+                            (CastlingSide::KingSide, Some(col))
+                        } else {
+                            (CastlingSide::QueenSide, Some(col))
+                        }
                     }
+                    _ => return Err(FenError::InvalidCastling(castling.to_string())),
+                };
+                let col = col.ok_or_else(illegal)?;
+                // The correct rook must be there for it to castle
+                match self.at_position(Position::new(color.get_home(), col)) {
+                    Some(piece) if piece.kind == PieceType::Rook && piece.color == color => {
+                        rights.set(color, side, Some(col));
+                    }
+                    _ => return Err(illegal()),
                 }
             }
         }
+        self.set_castling_rights(rights);
+        Ok(())
+    }
 
-        // Parse other info
-        board.whose_turn = Color::from_fen(to_move)?;
-        board.en_passant_target = Position::from_fen(en_passant_target)?;
+    /// Returns the castling section of a FEN string for the current
+    /// castling rights.
+    ///
+    /// Rooks that are the outermost on their side of the king are written as
+    /// `KQkq`, and any others using their file letter, as in X-FEN.
+    fn castling_fen(&self) -> String {
+        let rights = self.castling_rights();
+        let mut castling = String::new();
+        for color in [Color::White, Color::Black] {
+            for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+                if let Some(col) = rights.rook_col(color, side) {
+                    let c = if self.outermost_castling_rook(color, side) == Some(col) {
+                        match side {
+                            CastlingSide::KingSide => 'k',
+                            CastlingSide::QueenSide => 'q',
+                        }
+                    } else {
+                        (col as u8 + b'a') as char
+                    };
+                    castling.push(match color {
+                        Color::White => c.to_ascii_uppercase(),
+                        Color::Black => c,
+                    });
+                }
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+        castling
+    }
 
-        Ok(board)
+    /// Returns the column of the king of the given color, if it is on its
+    /// home row
+    fn home_king_col(&self, color: Color) -> Option<i8> {
+        let row = color.get_home();
+        (0..8).find(|col| {
+            matches!(
+                self.at_position(Position::new(row, *col)),
+                Some(piece) if piece.kind == PieceType::King && piece.color == color
+            )
+        })
+    }
+
+    /// Returns the column of the rook furthest from the king on the given
+    /// side along the home row, if the king is on its home row
+    fn outermost_castling_rook(&self, color: Color, side: CastlingSide) -> Option<i8> {
+        let row = color.get_home();
+        let king_col = self.home_king_col(color)?;
+        let direction = side.get_direction();
+        let mut col = if direction > 0 { 7 } else { 0 };
+        while col != king_col {
+            if let Some(piece) = self.at_position(Position::new(row, col)) {
+                if piece.kind == PieceType::Rook && piece.color == color {
+                    return Some(col);
+                }
+            }
+            col -= direction;
+        }
+        None
     }
+
+    /// Returns a FEN string describing the current position
+    pub fn to_fen(&self) -> String {
+        let mut positions = String::new();
+        for row in (0..8).rev() {
+            let mut spaces = 0;
+            for col in 0..8 {
+                if let Some(piece) = self.at_position(Position::new(row, col)) {
+                    if spaces > 0 {
+                        positions.push_str(&spaces.to_string());
+                        spaces = 0;
+                    }
+                    let c = piece.kind.letter();
+                    positions.push(match piece.color {
+                        Color::White => c,
+                        Color::Black => c.to_ascii_lowercase(),
+                    });
+                } else {
+                    spaces += 1;
+                }
+            }
+            if spaces > 0 {
+                positions.push_str(&spaces.to_string());
+            }
+            if row > 0 {
+                positions.push('/');
+            }
+        }
+
+        let to_move = match self.whose_turn {
+            Color::White => "w",
+            Color::Black => "b",
+        };
+        let en_passant_target = match self.en_passant_target {
+            Some(pos) => pos.to_string().to_ascii_lowercase(),
+            None => "-".to_string(),
+        };
+
+        format!(
+            "{} {} {} {} {} {}",
+            positions,
+            to_move,
+            self.castling_fen(),
+            en_passant_target,
+            self.half_move_clock.last().unwrap(),
+            self.num_moves,
+        )
+    }
+}
+
+/// Parse the half-move clock or full-move number of a FEN string, which
+/// can't be negative, and must fit in the board's counters
+fn parse_counter(counter: &str) -> Result<i32, FenError> {
+    // Parsing as unsigned rejects negative numbers, and as signed rejects
+    // ones too large to store
+    counter.parse::<u32>()?;
+    Ok(counter.parse::<i32>()?)
 }
@@ -0,0 +1,73 @@
+use alloc::vec::Vec;
+use core::{error::Error, fmt::Display};
+
+use super::{Board, FenError};
+
+/// Error when parsing a position given by `parse_position`
+#[derive(Debug)]
+pub enum PositionInputError {
+    /// The input looked like a FEN or EPD string, but couldn't be parsed
+    Fen(FenError),
+
+    /// The input was treated as SAN movetext from the starting position, but
+    /// a move couldn't be played
+    /// Includes the index of the offending token
+    Movetext(usize),
+}
+
+impl Display for PositionInputError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PositionInputError::Fen(e) => write!(f, "{}", e),
+            PositionInputError::Movetext(i) => {
+                write!(f, "token {} of the movetext isn't a legal move", i + 1)
+            }
+        }
+    }
+}
+
+impl Error for PositionInputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PositionInputError::Fen(e) => Some(e),
+            PositionInputError::Movetext(_) => None,
+        }
+    }
+}
+
+impl From<FenError> for PositionInputError {
+    fn from(e: FenError) -> Self {
+        PositionInputError::Fen(e)
+    }
+}
+
+/// Create a board from a position in whatever format it was given in.
+///
+/// The format is detected automatically:
+/// * A FEN string, such as
+///   `rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1`
+/// * An EPD record, such as
+///   `rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 id "e4";`
+/// * SAN movetext played from the starting position, such as `1. e4 e5`
+pub fn parse_position(input: &str) -> Result<Board, PositionInputError> {
+    let fields: Vec<&str> = input.split_ascii_whitespace().collect();
+
+    // Only FEN and EPD start with the piece placement
+    if fields.first().is_some_and(|f| f.contains('/')) {
+        let has_counters = fields.len() == 6
+            && fields[4..]
+                .iter()
+                .all(|f| f.chars().all(|c| c.is_ascii_digit()));
+        return if has_counters {
+            Ok(Board::from_fen(input)?)
+        } else {
+            Ok(Board::from_epd(input)?)
+        };
+    }
+
+    let mut board = Board::from_start();
+    board
+        .apply_movetext(input)
+        .map_err(PositionInputError::Movetext)?;
+    Ok(board)
+}
@@ -0,0 +1,134 @@
+use crate::game::{
+    bitboard::{
+        between, bishop_attacks, king_attacks, knight_attacks, line, pawn_attacks, rook_attacks,
+        Bitboard,
+    },
+    Color, PieceType, Position, Turn,
+};
+
+use super::Board;
+
+/// Restrictions on where the pieces of one color can move,
+/// worked out once per position so that each candidate move can be checked
+/// without making it.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct LegalMasks {
+    /// Color of the pieces being moved
+    color: Color,
+
+    /// Position of their king
+    king: Position,
+
+    /// Squares that a piece other than the king must move to, which are
+    /// every square when not in check, the checking piece and the squares
+    /// between it and the king when in check once, and no squares when in
+    /// double check
+    check_mask: Bitboard,
+
+    /// Pieces that can only move along the line between the king and the
+    /// piece pinning them
+    pinned: Bitboard,
+}
+
+impl LegalMasks {
+    /// Returns the squares the piece at the given position can move to
+    /// without exposing its king, if it isn't the king
+    fn allowed(&self, from: Position) -> Bitboard {
+        if self.pinned.contains(from) {
+            self.check_mask & line(self.king, from)
+        } else {
+            self.check_mask
+        }
+    }
+}
+
+impl Board {
+    /// Returns the squares containing pieces of the given color that attack
+    /// the given position, as if the board only contained the pieces in
+    /// `occupied`
+    pub(super) fn attackers_with(
+        &self,
+        position: Position,
+        color: Color,
+        occupied: Bitboard,
+    ) -> Bitboard {
+        let queens = self.pieces(PieceType::Queen, color);
+        ((knight_attacks(position) & self.pieces(PieceType::Knight, color))
+            | (king_attacks(position) & self.pieces(PieceType::King, color))
+            // A pawn attacks this square from where a pawn of the other color
+            // on this square would attack
+            | (pawn_attacks(!color, position) & self.pieces(PieceType::Pawn, color))
+            | (rook_attacks(position, occupied) & (self.pieces(PieceType::Rook, color) | queens))
+            | (bishop_attacks(position, occupied)
+                & (self.pieces(PieceType::Bishop, color) | queens)))
+            & occupied
+    }
+
+    /// Work out the checks and pins against the pieces of the given color,
+    /// or `None` if they have no king, in which case they have no legal
+    /// moves
+    pub(super) fn legal_masks(&self, color: Color) -> Option<LegalMasks> {
+        let king = self.find_king(color)?;
+        let occupied = self.occupied();
+
+        let checkers = self.attackers(king, !color);
+        let check_mask = match checkers.count() {
+            0 => !Bitboard::EMPTY,
+            1 => checkers | between(king, checkers.first().unwrap()),
+            _ => Bitboard::EMPTY,
+        };
+
+        // Enemy sliders that would attack the king if it weren't for the
+        // pieces in the way
+        let queens = self.pieces(PieceType::Queen, !color);
+        let their_pieces = self.occupied_by(!color);
+        let snipers = (rook_attacks(king, their_pieces)
+            & (self.pieces(PieceType::Rook, !color) | queens))
+            | (bishop_attacks(king, their_pieces)
+                & (self.pieces(PieceType::Bishop, !color) | queens));
+        let mut pinned = Bitboard::EMPTY;
+        for sniper in snipers {
+            let blockers = between(king, sniper) & occupied;
+            if blockers.count() == 1 {
+                pinned |= blockers & self.occupied_by(color);
+            }
+        }
+
+        Some(LegalMasks {
+            color,
+            king,
+            check_mask,
+            pinned,
+        })
+    }
+
+    /// Returns whether the given candidate turn, which must follow the
+    /// movement rules, doesn't leave the moving player's king in check
+    pub(super) fn is_legal_with(&self, turn: &Turn, masks: &LegalMasks) -> bool {
+        let color = masks.color;
+        let from = Bitboard::from_position(turn.from);
+        let to = Bitboard::from_position(turn.to);
+
+        if let Some((rook_from, rook_to)) = turn.additional_move {
+            // Castling has already checked the squares the king moves
+            // through, but the rook may have been blocking an attack on the
+            // king's destination
+            let occupied = (self.occupied() & !from & !Bitboard::from_position(rook_from))
+                | to
+                | Bitboard::from_position(rook_to);
+            self.attackers_with(turn.to, !color, occupied).is_empty()
+        } else if turn.kind == PieceType::King {
+            // The king can't hide from a slider by moving along its line
+            let occupied = self.occupied() & !from;
+            self.attackers_with(turn.to, !color, occupied).is_empty()
+        } else if let Some(captured) = turn.capture.filter(|&pos| pos != turn.to) {
+            // En passant removes two pieces from the row, so may expose the
+            // king in ways that pins don't describe
+            let captured = Bitboard::from_position(captured);
+            let occupied = (self.occupied() & !from & !captured) | to;
+            self.attackers_with(masks.king, !color, occupied).is_empty()
+        } else {
+            masks.allowed(turn.from).contains(turn.to)
+        }
+    }
+}
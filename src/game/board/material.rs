@@ -0,0 +1,183 @@
+use crate::game::{Color, PieceType, Position};
+use alloc::vec;
+
+use super::Board;
+
+impl Board {
+    /// Returns the total value of the pieces of the given color, counted in
+    /// pawns, as given by `PieceType::value`
+    pub fn material(&self, color: Color) -> u32 {
+        self.material[color.index()]
+    }
+
+    /// Returns how many more pawns worth of material the given color has
+    /// than their opponent, which is negative if they are behind
+    pub fn material_balance(&self, color: Color) -> i32 {
+        self.material(color) as i32 - self.material(!color) as i32
+    }
+
+    /// Returns the number of pieces of the given kind and color
+    pub fn piece_count(&self, color: Color, kind: PieceType) -> u32 {
+        self.pieces(kind, color).count()
+    }
+
+    /// Returns whether it's a draw by insufficient material, meaning neither
+    /// player has enough pieces left to checkmate: a king against a king,
+    /// with at most one knight, or with any number of bishops that are all
+    /// on squares of the same color
+    pub fn is_insufficient_material(&self) -> bool {
+        let of_kind = |kind: PieceType| self.pieces[kind.index()];
+        if !(of_kind(PieceType::Pawn) | of_kind(PieceType::Rook) | of_kind(PieceType::Queen))
+            .is_empty()
+        {
+            return false;
+        }
+        let bishops = of_kind(PieceType::Bishop);
+        match (of_kind(PieceType::Knight).count(), bishops.count()) {
+            (0, _) => {
+                let mut colors = bishops.map(|pos| pos.color());
+                let first = colors.next();
+                colors.all(|c| Some(c) == first)
+            }
+            (1, 0) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns whether the given color has enough material that it could
+    /// ever checkmate, given the right moves from both players, such as when
+    /// deciding whether running out of time loses or draws.
+    ///
+    /// A lone knight or bishops on a single color can only checkmate if the
+    /// opponent has pieces that can block their own king in, which may be
+    /// any of their pieces other than the king, except bishops on the same
+    /// color of square as the bishops giving mate.
+    pub fn has_mating_material(&self, color: Color) -> bool {
+        let own = self.colors[color.index()];
+        let opponent = self.colors[(!color).index()] & !self.pieces[PieceType::King.index()];
+        let of_kind = |kind: PieceType| self.pieces[kind.index()] & own;
+        if !(of_kind(PieceType::Pawn) | of_kind(PieceType::Rook) | of_kind(PieceType::Queen))
+            .is_empty()
+        {
+            return true;
+        }
+        let bishops = of_kind(PieceType::Bishop);
+        match (of_kind(PieceType::Knight).count(), bishops.count()) {
+            (0, 0) => false,
+            (1, 0) => !opponent.is_empty(),
+            (0, _) => {
+                let mut colors = bishops.map(|pos| pos.color());
+                let first = colors.next().unwrap();
+                if !colors.all(|c| c == first) {
+                    return true;
+                }
+                // Bishops on the same color of square as these can never
+                // block the mated king in
+                let same_color_bishops = (opponent & self.pieces[PieceType::Bishop.index()])
+                    .filter(|pos| pos.color() == first)
+                    .count();
+                opponent.count() as usize > same_color_bishops
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns whether the position is dead, meaning that no sequence of
+    /// legal moves can lead to checkmate.
+    ///
+    /// This is conservative: it only returns `true` when the position is
+    /// provably dead, either due to insufficient material, or because only
+    /// kings and a locked pawn wall remain. Other dead positions may not be
+    /// detected.
+    pub fn is_dead_position(&self) -> bool {
+        self.is_insufficient_material() || self.is_locked_pawn_wall()
+    }
+
+    /// Returns whether only kings and pawns remain, where no pawn can ever
+    /// move and neither king can ever capture a pawn
+    fn is_locked_pawn_wall(&self) -> bool {
+        if self.en_passant_target.is_some() || self.is_check() {
+            return false;
+        }
+        let mut has_pawns = false;
+        for pos in self.occupied() {
+            match self.squares[pos.pos()] {
+                Some(piece) if piece.kind == PieceType::Pawn => {
+                    has_pawns = true;
+                    // Must be blocked by another pawn
+                    if !self.is_pawn_at(pos.offset(piece.color.get_direction(), 0), None) {
+                        return false;
+                    }
+                    // And unable to capture
+                    for c in [-1, 1] {
+                        let target = pos.offset(piece.color.get_direction(), c);
+                        if self.is_pawn_at(target, Some(!piece.color)) {
+                            return false;
+                        }
+                    }
+                }
+                Some(piece) if piece.kind == PieceType::King => {}
+                Some(_) => return false,
+                None => {}
+            }
+        }
+        // Since no pawn can move or capture, the only pieces that can ever
+        // move are the kings, so make sure neither can reach a pawn it could
+        // capture
+        has_pawns
+            && !self.king_can_reach_pawn(Color::White)
+            && !self.king_can_reach_pawn(Color::Black)
+    }
+
+    /// Returns whether there is a pawn at the given position, optionally of
+    /// the given color
+    fn is_pawn_at(&self, pos: Option<Position>, color: Option<Color>) -> bool {
+        matches!(
+            pos.and_then(|pos| self.at_position(pos)),
+            Some(piece) if piece.kind == PieceType::Pawn && color.is_none_or(|c| piece.color == c)
+        )
+    }
+
+    /// Returns whether the given square is attacked by a pawn of the given
+    /// color
+    fn is_attacked_by_pawn(&self, pos: Position, color: Color) -> bool {
+        [-1, 1]
+            .into_iter()
+            .any(|c| self.is_pawn_at(pos.offset(-color.get_direction(), c), Some(color)))
+    }
+
+    /// Returns whether the king of the given color could ever walk to an
+    /// undefended enemy pawn, assuming that no pawns move. Squares are
+    /// considered reachable even if the other king guards them, which only
+    /// makes this more likely to return `true`.
+    fn king_can_reach_pawn(&self, color: Color) -> bool {
+        let Some(king) = self.find_king(color) else {
+            return false;
+        };
+        let mut reachable = [false; 64];
+        let mut to_visit = vec![king];
+        reachable[to_visit[0].pos()] = true;
+        while let Some(pos) = to_visit.pop() {
+            for r in [-1, 0, 1] {
+                for c in [-1, 0, 1] {
+                    let Some(next) = pos.offset(r, c) else {
+                        continue;
+                    };
+                    if reachable[next.pos()] || self.is_attacked_by_pawn(next, !color) {
+                        continue;
+                    }
+                    if self.is_pawn_at(Some(next), Some(!color)) {
+                        // An undefended enemy pawn, which could be captured
+                        return true;
+                    }
+                    if self.is_pawn_at(Some(next), None) {
+                        continue;
+                    }
+                    reachable[next.pos()] = true;
+                    to_visit.push(next);
+                }
+            }
+        }
+        false
+    }
+}
@@ -1,16 +1,52 @@
+use alloc::{vec, vec::Vec};
+
+mod batch;
+mod builder;
+mod cache;
+mod check;
+mod claims;
+mod diagnose;
+mod edit;
 mod fen;
+mod input;
+mod legal;
+mod material;
+mod move_gen;
 mod moves;
+mod perft;
+mod regions;
+mod repetition;
+mod san;
+mod state;
+mod stats;
 mod turns;
+mod uci;
+mod validate;
 
 use arr_macro::arr;
+pub use batch::{PositionBatch, BATCH_PLANES};
+pub use builder::BoardBuilder;
+use cache::PlyCache;
+pub use check::{check_moves, MoveCheck};
+pub use claims::ClaimError;
+use core::fmt::{Debug, Display};
+pub use diagnose::IllegalMoveReason;
 pub use fen::FenError;
-use std::fmt::{Debug, Display};
+pub use input::{parse_position, PositionInputError};
+pub use move_gen::MoveGen;
+pub use perft::{perft, PerftCache};
+pub use san::SanError;
+pub use state::BoardState;
+pub use stats::Stats;
+pub use turns::MoveError;
+pub use validate::PositionProblem;
 
 use super::{
+    bitboard::Bitboard,
     game_state::{DrawReason, GameState, WinReason},
-    piece::{Piece},
+    piece::Piece,
     turn::Turn,
-    Color, PieceType, Position,
+    zobrist, CastlingRights, Color, PieceType, Position, Rules,
 };
 
 #[derive(Debug, Clone)]
@@ -21,6 +57,28 @@ pub struct Board {
     /// 8x8 board
     squares: [Option<Piece>; 8 * 8],
 
+    /// Squares containing each kind of piece, indexed by `PieceType::index`,
+    /// kept in sync with `squares`
+    pieces: [Bitboard; 6],
+
+    /// Squares containing pieces of each color, indexed by `Color::index`,
+    /// kept in sync with `squares`
+    colors: [Bitboard; 2],
+
+    /// Square of each king, indexed by `Color::index`, so that it doesn't
+    /// need to be searched for when checking for attacks
+    kings: [Option<Position>; 2],
+
+    /// Total value of each color's pieces, indexed by `Color::index`,
+    /// updated as pieces are captured and promoted
+    material: [u32; 2],
+
+    /// Zobrist hash of the pieces on the board, updated as they move
+    piece_hash: u64,
+
+    /// Zobrist hash of just the pawns, updated as they move
+    pawn_hash: u64,
+
     /// Whose turn it is to move
     whose_turn: Color,
 
@@ -28,13 +86,45 @@ pub struct Board {
     moves: Vec<Turn>,
 
     /// Number of half moves since pawn push or capture
-    half_move_clock: Vec<i8>,
+    half_move_clock: Vec<i32>,
 
     /// Number of full moves
     num_moves: i32,
 
     /// Position to target for en passant
     en_passant_target: Option<Position>,
+
+    /// En passant target before each move, restored when it is undone
+    en_passant_history: Vec<Option<Position>>,
+
+    /// Castling rights before each move, with the current rights last
+    castling_rights: Vec<CastlingRights>,
+
+    /// Zobrist hash of each position reached, for detecting repetitions
+    position_hashes: Vec<u64>,
+
+    /// Indexes into `position_hashes` of the positions reached by turns that
+    /// can't be reversed, or by null moves, before which no position can be
+    /// repeated
+    irreversible_plies: Vec<usize>,
+
+    /// Outcome of the game if it was ended by a player resigning or running
+    /// out of time, or both players agreeing to a draw. This stays when
+    /// turns are undone, until the game is resumed.
+    ended_by_players: Option<GameState>,
+
+    /// Move generation and search statistics, if they are being collected
+    stats: Option<Stats>,
+
+    /// When the game ends
+    rules: Rules,
+
+    /// Null moves that have been made, as the number of turns that had been
+    /// made beforehand and the en passant target that they cleared
+    null_moves: Vec<(usize, Option<Position>)>,
+
+    /// Answers to queries about the current position
+    cache: PlyCache,
 }
 
 impl Default for Board {
@@ -42,11 +132,26 @@ impl Default for Board {
         Self {
             captures: Default::default(),
             squares: arr![None; 64],
+            pieces: [Bitboard::EMPTY; 6],
+            colors: [Bitboard::EMPTY; 2],
+            kings: [None; 2],
+            material: [0; 2],
+            piece_hash: 0,
+            pawn_hash: 0,
             whose_turn: Color::White,
             moves: Default::default(),
             half_move_clock: vec![0],
             en_passant_target: None,
+            en_passant_history: vec![],
             num_moves: 1,
+            castling_rights: vec![CastlingRights::default()],
+            stats: None,
+            position_hashes: vec![],
+            irreversible_plies: vec![],
+            ended_by_players: None,
+            rules: Rules::default(),
+            null_moves: vec![],
+            cache: PlyCache::default(),
         }
     }
 }
@@ -54,7 +159,10 @@ impl Default for Board {
 impl Board {
     /// Create a board in the starting position
     pub fn from_start() -> Self {
-        let mut board = Self::default();
+        let mut board = Self {
+            castling_rights: vec![CastlingRights::from_start()],
+            ..Default::default()
+        };
 
         let piece_order = [
             PieceType::Rook,
@@ -68,19 +176,22 @@ impl Board {
         ];
 
         // Pieces
-        for (piece, i) in piece_order.iter().zip(0..8) {
-            board.squares[i] = Some(Piece::new(*piece, Color::White));
-        }
-        for (piece, i) in piece_order.iter().zip(56..64) {
-            board.squares[i] = Some(Piece::new(*piece, Color::Black));
+        for (piece, col) in piece_order.iter().zip(0..8) {
+            board.put_piece(Position::new(0, col), Piece::new(*piece, Color::White));
+            board.put_piece(Position::new(7, col), Piece::new(*piece, Color::Black));
         }
         // Pawns
-        for i in 8..16 {
-            board.squares[i] = Some(Piece::new(PieceType::Pawn, Color::White));
-        }
-        for i in 48..56 {
-            board.squares[i] = Some(Piece::new(PieceType::Pawn, Color::Black));
+        for col in 0..8 {
+            board.put_piece(
+                Position::new(1, col),
+                Piece::new(PieceType::Pawn, Color::White),
+            );
+            board.put_piece(
+                Position::new(6, col),
+                Piece::new(PieceType::Pawn, Color::Black),
+            );
         }
+        board.refresh_position_hash();
 
         board
     }
@@ -90,11 +201,152 @@ impl Board {
         self.squares[position.pos()].as_ref()
     }
 
+    /// Returns the squares containing pieces of the given kind and color
+    pub fn pieces(&self, kind: PieceType, color: Color) -> Bitboard {
+        self.pieces[kind.index()] & self.colors[color.index()]
+    }
+
+    /// Returns the squares containing pieces of the given color
+    pub fn occupied_by(&self, color: Color) -> Bitboard {
+        self.colors[color.index()]
+    }
+
+    /// Returns the squares containing any piece
+    pub fn occupied(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
+    }
+
+    /// Returns the Zobrist hash of the position, which is the same for
+    /// positions that are the same for the purposes of repetition.
+    ///
+    /// The pieces are hashed as they move, and the player to move, castling
+    /// rights and en passant target, which are restored from history on
+    /// undo, are combined in when this is called.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.piece_hash
+            ^ zobrist::state_key(
+                self.whose_turn,
+                self.castling_rights(),
+                self.en_passant_target,
+            )
+    }
+
+    /// Returns the Zobrist hash of just the pawns, which changes far less
+    /// often than the full hash, for caching evaluations of the pawn
+    /// structure
+    pub fn pawn_hash(&self) -> u64 {
+        self.pawn_hash
+    }
+
+    /// Place a piece on an empty square
+    fn put_piece(&mut self, position: Position, piece: Piece) {
+        assert!(
+            self.squares[position.pos()].is_none(),
+            "Square {} is occupied",
+            position
+        );
+        self.pieces[piece.kind.index()].set(position);
+        self.colors[piece.color.index()].set(position);
+        self.material[piece.color.index()] += piece.kind.value();
+        self.piece_hash ^= zobrist::piece_key(piece.kind, piece.color, position);
+        if piece.kind == PieceType::Pawn {
+            self.pawn_hash ^= zobrist::piece_key(piece.kind, piece.color, position);
+        }
+        if piece.kind == PieceType::King {
+            self.update_king(piece.color);
+        }
+        self.squares[position.pos()] = Some(piece);
+        self.cache.clear();
+    }
+
+    /// Remove and return the piece on a square, if there is one
+    fn take_piece(&mut self, position: Position) -> Option<Piece> {
+        let piece = self.squares[position.pos()].take()?;
+        self.pieces[piece.kind.index()].clear(position);
+        self.colors[piece.color.index()].clear(position);
+        self.material[piece.color.index()] -= piece.kind.value();
+        self.piece_hash ^= zobrist::piece_key(piece.kind, piece.color, position);
+        if piece.kind == PieceType::Pawn {
+            self.pawn_hash ^= zobrist::piece_key(piece.kind, piece.color, position);
+        }
+        if piece.kind == PieceType::King {
+            self.update_king(piece.color);
+        }
+        self.cache.clear();
+        Some(piece)
+    }
+
+    /// Update the cached square of the king of the given color after a king
+    /// is placed or removed. While editing, there may be more than one, in
+    /// which case the lowest one is used.
+    fn update_king(&mut self, color: Color) {
+        self.kings[color.index()] = self.pieces(PieceType::King, color).first();
+    }
+
     /// Return whose turn it is
     pub fn whose_turn(&self) -> Color {
         self.whose_turn
     }
 
+    /// Return which castling moves are still allowed
+    pub fn castling_rights(&self) -> CastlingRights {
+        *self.castling_rights.last().unwrap()
+    }
+
+    /// Replace the current castling rights
+    pub fn set_castling_rights(&mut self, rights: CastlingRights) {
+        *self.castling_rights.last_mut().unwrap() = rights;
+        self.refresh_position_hash();
+    }
+
+    /// Returns the square that can be moved to in order to capture en
+    /// passant, if the previous turn was a pawn moving two squares
+    pub fn en_passant_target(&self) -> Option<Position> {
+        self.en_passant_target
+    }
+
+    /// Set or clear the en passant target.
+    ///
+    /// The target must be just behind a pawn of the player who isn't to
+    /// move, as if it had just moved two squares, otherwise the target is
+    /// left unchanged and an error is returned.
+    pub fn set_en_passant_target(
+        &mut self,
+        target: Option<Position>,
+    ) -> Result<(), PositionProblem> {
+        if let Some(pos) = target {
+            if !self.is_en_passant_valid(pos) {
+                return Err(PositionProblem::InvalidEnPassant(pos));
+            }
+        }
+        self.en_passant_target = target;
+        self.refresh_position_hash();
+        Ok(())
+    }
+
+    /// Returns the rules deciding when the game ends
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Replace the rules deciding when the game ends
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
+        self.cache.clear();
+    }
+
+    /// Returns the number of half moves since the last capture or pawn
+    /// push, as used by the 50 move rule
+    pub fn halfmove_clock(&self) -> u32 {
+        *self.half_move_clock.last().unwrap() as u32
+    }
+
+    /// Returns the number of the current full move, starting at 1 and
+    /// incrementing after each move by black
+    pub fn fullmove_number(&self) -> u32 {
+        self.num_moves as u32
+    }
+
     /// Returns a reference to the previous turn
     pub fn get_prev_turn(&self) -> Option<&Turn> {
         if self.moves.is_empty() {
@@ -106,23 +358,20 @@ impl Board {
 
     /// Returns whether position is check
     pub fn is_check(&self) -> bool {
-        self.is_king_attacked(self.whose_turn)
+        *self
+            .cache
+            .is_check
+            .get_or_init(|| self.is_king_attacked(self.whose_turn))
     }
 
     /// Returns whether position is checkmate
     pub fn is_checkmate(&mut self) -> bool {
-        self.is_check() && self.do_get_moves().is_empty()
+        self.is_check() && !self.has_legal_move()
     }
 
     /// Returns whether the position is stalemate
     pub fn is_stalemate(&mut self) -> bool {
-        !self.is_check() && self.do_get_moves().is_empty()
-    }
-
-    /// Returns whether the position is a draw by threefold repetition
-    pub fn is_threefold_repetition(&self) -> bool {
-        // todo!()
-        false
+        !self.is_check() && !self.has_legal_move()
     }
 
     /// Returns whether its a draw by the 50 move rule
@@ -130,53 +379,144 @@ impl Board {
         *self.half_move_clock.last().unwrap() >= 100
     }
 
-    /// Returns whether it's a draw by insufficient repetition
-    pub fn is_insufficient_material(&self) -> bool {
-        // todo!()
-        false
+    /// Returns whether its a draw by the 75 move rule, which unlike the 50
+    /// move rule ends the game without needing to be claimed
+    pub fn is_75_move_rule(&self) -> bool {
+        *self.half_move_clock.last().unwrap() >= 150
+    }
+
+    /// Returns whether the move limit given by the rules has been reached
+    fn is_move_limit(&self) -> bool {
+        self.halfmove_clock() >= 2 * self.rules.move_limit
+    }
+
+    /// Returns whether the repetition limit given by the rules has been
+    /// reached
+    fn is_repetition_limit(&self) -> bool {
+        self.repetition_count() >= self.rules.repetition_limit
     }
 
     /// Returns whether the game is a draw
     pub fn is_draw(&mut self) -> bool {
-        !self.is_checkmate()
-            && (self.is_stalemate()
-                || self.is_threefold_repetition()
-                || self.is_50_move_rule()
-                || self.is_insufficient_material())
+        matches!(self.get_game_state(), GameState::Draw(_))
     }
 
     /// Returns whether the game is over
     pub fn is_game_over(&mut self) -> bool {
-        self.is_draw() || self.is_checkmate()
+        self.get_game_state() != GameState::Playing
     }
 
-    /// Returns the state of the game
+    /// End the game with the given player resigning.
+    ///
+    /// Fails if the game is already over.
+    pub fn resign(&mut self, color: Color) -> Result<(), MoveError> {
+        self.end_by_players(GameState::Win(!color, WinReason::Resigned))
+    }
+
+    /// End the game with the given player running out of time.
+    ///
+    /// This is a loss, unless the opponent doesn't have enough material to
+    /// ever checkmate and the rules make that a draw. Fails if the game is
+    /// already over.
+    pub fn time_out(&mut self, color: Color) -> Result<(), MoveError> {
+        let state =
+            if self.rules.timeout_insufficient_material_draw && !self.has_mating_material(!color) {
+                GameState::Draw(DrawReason::TimeOut)
+            } else {
+                GameState::Win(!color, WinReason::TimeOut)
+            };
+        self.end_by_players(state)
+    }
+
+    /// End the game with both players agreeing to a draw.
+    ///
+    /// Fails if the game is already over.
+    pub fn agree_draw(&mut self) -> Result<(), MoveError> {
+        self.end_by_players(GameState::Draw(DrawReason::MutualAgreement))
+    }
+
+    fn end_by_players(&mut self, state: GameState) -> Result<(), MoveError> {
+        if self.is_game_over() {
+            return Err(MoveError::GameOver);
+        }
+        self.ended_by_players = Some(state);
+        self.cache.clear();
+        Ok(())
+    }
+
+    /// Take back a resignation, time out or agreed draw, so that the game
+    /// can carry on, returning the outcome that was taken back.
+    ///
+    /// Undoing turns doesn't do this by itself, so the outcome is kept when
+    /// looking back through the game.
+    pub fn resume(&mut self) -> Option<GameState> {
+        let state = self.ended_by_players.take();
+        self.cache.clear();
+        state
+    }
+
+    /// Returns the state of the game.
+    ///
+    /// Check detection and move generation are each done at most once, so
+    /// this is cheaper than calling the individual predicates in turn. The
+    /// result is remembered until the position changes.
     pub fn get_game_state(&mut self) -> GameState {
-        if self.is_checkmate() {
-            GameState::Win(!self.whose_turn, WinReason::Checkmate)
-        } else if self.is_stalemate() {
-            GameState::Draw(DrawReason::Stalemate)
-        } else if self.is_50_move_rule() {
-            GameState::Draw(DrawReason::FiftyMoveRule)
-        } else if self.is_threefold_repetition() {
-            GameState::Draw(DrawReason::ThreefoldRepetition)
+        self.cache
+            .game_state
+            .get_or_init(|| self.compute_game_state())
+            .clone()
+    }
+
+    fn compute_game_state(&self) -> GameState {
+        if let Some(state) = &self.ended_by_players {
+            return state.clone();
+        }
+        if !self.has_legal_move() {
+            return if self.is_check() {
+                GameState::Win(!self.whose_turn, WinReason::Checkmate)
+            } else {
+                GameState::Draw(DrawReason::Stalemate)
+            };
+        }
+        match self.get_draw_reason() {
+            Some(reason) => GameState::Draw(reason),
+            None => GameState::Playing,
+        }
+    }
+
+    /// Returns the reason the game is drawn, other than stalemate, which
+    /// needs move generation
+    fn get_draw_reason(&self) -> Option<DrawReason> {
+        if self.is_75_move_rule() {
+            Some(DrawReason::SeventyFiveMoveRule)
+        } else if self.is_fivefold_repetition() {
+            Some(DrawReason::FivefoldRepetition)
+        } else if self.is_move_limit() {
+            Some(match self.rules.move_limit {
+                50 => DrawReason::FiftyMoveRule,
+                moves => DrawReason::MoveLimit(moves),
+            })
+        } else if self.is_repetition_limit() {
+            Some(match self.rules.repetition_limit {
+                3 => DrawReason::ThreefoldRepetition,
+                times => DrawReason::RepetitionLimit(times),
+            })
         } else if self.is_insufficient_material() {
-            GameState::Draw(DrawReason::InsufficientMaterial)
+            Some(DrawReason::InsufficientMaterial)
+        } else if self.is_dead_position() {
+            Some(DrawReason::DeadPosition)
         } else {
-            GameState::Playing
+            None
         }
     }
 }
 
 impl Display for Board {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "To move: {}", self.whose_turn)?;
         writeln!(f, "Pieces:")?;
-        for (i, square) in self.squares.iter().enumerate() {
-            if let Some(piece) = square {
-                let pos = Position::from(i as i8);
-                writeln!(f, "- {}: {}", pos, piece)?;
-            }
+        for pos in self.occupied() {
+            writeln!(f, "- {}: {}", pos, self.squares[pos.pos()].unwrap())?;
         }
         writeln!(f, "Captures:")?;
         for cap in self.captures.iter() {
@@ -0,0 +1,62 @@
+use crate::game::{Bitboard, Move, MoveList};
+
+use super::{legal::LegalMasks, Board};
+
+/// Iterator over the legal moves of the player whose turn it is, which
+/// generates them one piece at a time as they are needed.
+///
+/// This is cheaper than `Board::generate_moves` when only some of the moves
+/// are needed, such as when checking whether any move exists. Like
+/// `generate_moves`, it doesn't check whether the game has already been
+/// drawn.
+#[derive(Debug, Clone)]
+pub struct MoveGen<'a> {
+    board: &'a Board,
+
+    /// Checks and pins, or `None` if the player has no king, so has no
+    /// legal moves
+    masks: Option<LegalMasks>,
+
+    /// Pieces whose moves haven't been generated yet
+    remaining: Bitboard,
+
+    /// Candidate moves of the current piece, which may not be legal
+    candidates: MoveList,
+
+    /// Index of the next candidate to check
+    next: usize,
+}
+
+impl<'a> MoveGen<'a> {
+    /// Start generating the moves in the given position
+    pub fn new(board: &'a Board) -> Self {
+        let color = board.whose_turn();
+        Self {
+            board,
+            masks: board.legal_masks(color),
+            remaining: board.occupied_by(color),
+            candidates: MoveList::new(),
+            next: 0,
+        }
+    }
+}
+
+impl Iterator for MoveGen<'_> {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let masks = self.masks.as_ref()?;
+        loop {
+            while let Some(turn) = self.candidates.get(self.next) {
+                self.next += 1;
+                if self.board.is_legal_with(turn, masks) {
+                    return Some(Move::from(turn));
+                }
+            }
+            let pos = self.remaining.next()?;
+            self.candidates.clear();
+            self.next = 0;
+            self.board.piece_moves(pos, &mut self.candidates);
+        }
+    }
+}
@@ -1,76 +1,56 @@
 use crate::game::{
-    piece::{KNIGHT_MOVES, PROMOTABLE_TYPES},
-    PieceType, Position, Turn, Color,
+    bitboard::{bishop_attacks, king_attacks, knight_attacks, rook_attacks, Bitboard},
+    piece::PROMOTABLE_TYPES,
+    CastlingSide, Color, MoveList, PieceType, Position, Turn,
 };
+use alloc::{vec, vec::Vec};
 
-use super::Board;
+use super::{legal::LegalMasks, Board, IllegalMoveReason, MoveGen};
 
 impl Board {
+    /// Returns the squares containing pieces of the given color that are
+    /// attacking the given position
+    pub fn attackers(&self, position: Position, color: Color) -> Bitboard {
+        self.attackers_with(position, color, self.occupied())
+    }
+
     /// Returns `true` if a piece of the given color is attacking the given
     /// position
     pub fn are_pieces_attacking(&self, position: Position, color: Color) -> bool {
-        // Lines
-        for r in [-1, 0, 1] {
-            for c in [-1, 0, 1] {
-                if r == 0 && c == 0 {
-                    continue;
-                }
-                let mut pos = position;
-                while let Some(p) = pos.offset(r, c) {
-                    pos = p;
-                    if let Some(piece) = self.at_position(pos) {
-                        // If that piece is of the correct color and attacks
-                        // this square
-                        if piece.color == color && piece.could_move_to(pos, position, self) {
-                            return true;
-                        }
-                        // Otherwise, no other pieces in this line can attack
-                        break;
-                    }
-                }
-            }
-        }
-
-        // Knight positions
-        // This sorta defeats the purpose of the implementation of
-        // piece.could_knight_move_to, but at least it makes it more efficient
-        for (r, c) in KNIGHT_MOVES {
-            if let Some(pos) = position.offset(r, c) {
-                if let Some(piece) = self.at_position(pos) {
-                    if piece.kind == PieceType::Knight && piece.color == color {
-                        return true;
-                    }
-                }
-            }
-        }
+        !self.attackers(position, color).is_empty()
+    }
 
-        false
+    /// Returns the positions of all pieces of the given color that are
+    /// attacking the given position
+    pub fn attackers_of(&self, position: Position, color: Color) -> Vec<Position> {
+        self.attackers(position, color).collect()
     }
 
-    /// Find the king of a particular color
-    fn find_king(&self, color: Color) -> Position {
-        // This is pretty inefficient - improve this at some point
-        for i in 0..64 {
-            let pos = Position::from(i);
-            if let Some(piece) = self.at_position(pos) {
-                if piece.kind == PieceType::King && piece.color == color {
-                    return pos;
-                }
-            }
+    /// Returns the positions of all pieces attacking the king of the player
+    /// whose turn it is, which is none if they have no king
+    pub fn checkers(&self) -> Vec<Position> {
+        match self.find_king(self.whose_turn) {
+            Some(king) => self.attackers_of(king, !self.whose_turn),
+            None => vec![],
         }
-        println!("{}", self);
-        panic!("No king");
     }
 
-    /// Returns whether the king of the given color is under attack
+    /// Find the king of a particular color, if it is on the board
+    pub(super) fn find_king(&self, color: Color) -> Option<Position> {
+        self.kings[color.index()]
+    }
+
+    /// Returns whether the king of the given color is under attack, which
+    /// it isn't if it isn't on the board
     pub fn is_king_attacked(&self, color: Color) -> bool {
-        self.are_pieces_attacking(self.find_king(color), !color)
+        self.find_king(color)
+            .is_some_and(|king| self.are_pieces_attacking(king, !color))
     }
 
     /// Returns whether a move is legal - ie whether the other player
     /// is capable of capturing the king after the move is made
-    pub fn is_move_legal(&mut self, turn: Turn) -> bool {
-        self.make_turn(turn);
+    pub fn is_move_legal(&mut self, turn: &Turn) -> bool {
+        self.make_turn(*turn);
 
         let valid = !self.is_king_attacked(!self.whose_turn);
 
@@ -81,8 +61,14 @@ impl Board {
 
     /// Returns all possible moves that can be made
     pub fn get_moves(&mut self) -> Vec<Turn> {
-        // If it's threefold repetition or 50 move rule, skip all the checks
-        if self.is_threefold_repetition() || self.is_50_move_rule() {
+        // If the game was ended by the players, or drawn by repetition or
+        // the move limit, skip all the checks
+        if self.ended_by_players.is_some()
+            || self.is_repetition_limit()
+            || self.is_move_limit()
+            || self.is_fivefold_repetition()
+            || self.is_75_move_rule()
+        {
             vec![]
         } else {
             self.do_get_moves()
@@ -90,16 +76,81 @@ impl Board {
     }
 
     pub fn do_get_moves(&mut self) -> Vec<Turn> {
-        let mut turns = vec![];
-        for i in 0..64 {
-            let pos = Position::from(i);
-            if let Some(piece) = self.at_position(pos) {
-                if piece.color == self.whose_turn() {
-                    turns.extend(self.get_piece_moves(pos));
-                }
+        let mut moves = MoveList::new();
+        self.generate_moves(&mut moves);
+        moves.into()
+    }
+
+    /// Add all the legal moves for the player whose turn it is to the given
+    /// list, without allocating.
+    ///
+    /// Unlike `get_moves`, this doesn't check whether the game has already
+    /// been drawn.
+    pub fn generate_moves(&mut self, moves: &mut MoveList) {
+        let start = self.stats_timer();
+        let first = moves.len();
+        // Without a king, such as after clearing the board, there are no
+        // legal moves
+        if let Some(masks) = self.legal_masks(self.whose_turn) {
+            for pos in self.occupied_by(self.whose_turn) {
+                self.piece_moves(pos, moves);
             }
+            self.retain_legal(first, &masks, moves);
         }
-        turns
+        self.record_generation(start, moves.iter().skip(first));
+    }
+
+    /// Replace the contents of the given vector with all the legal moves for
+    /// the player whose turn it is, so that one buffer can be reused for
+    /// each ply of a search.
+    ///
+    /// Like `generate_moves`, this doesn't check whether the game has
+    /// already been drawn.
+    pub fn generate_moves_into(&mut self, moves: &mut Vec<Turn>) {
+        moves.clear();
+        let mut list = MoveList::new();
+        self.generate_moves(&mut list);
+        moves.extend(list);
+    }
+
+    /// Returns whether the player whose turn it is has any legal moves,
+    /// stopping as soon as one is found
+    pub fn has_legal_move(&self) -> bool {
+        *self
+            .cache
+            .has_legal_move
+            .get_or_init(|| MoveGen::new(self).next().is_some())
+    }
+
+    /// Find the legal turn that moves the piece at `from` to `to`, promoting
+    /// to the given kind of piece if it is a pawn reaching the last rank.
+    ///
+    /// Castling may be given either as the king moving to its destination,
+    /// or as the king moving onto its own rook. This saves callers, such as
+    /// user interfaces, from needing to construct turns themselves.
+    pub fn find_move(
+        &mut self,
+        from: Position,
+        to: Position,
+        promote_to: Option<PieceType>,
+    ) -> Option<Turn> {
+        let moves = self.do_get_moves();
+        let matching = |turn: &&Turn| turn.from == from && turn.promote_to == promote_to;
+        // In Chess960, the king may be able to castle onto a square it could
+        // also step to, in which case the ordinary move is meant
+        moves
+            .iter()
+            .filter(matching)
+            .find(|turn| turn.to == to && turn.additional_move.is_none())
+            .or_else(|| {
+                moves.iter().filter(matching).find(|turn| {
+                    turn.to == to
+                        || turn
+                            .additional_move
+                            .is_some_and(|(rook_from, _)| rook_from == to)
+                })
+            })
+            .copied()
     }
 
     /// Return the moves that can be legally made by a piece at the given
@@ -107,14 +158,27 @@ impl Board {
     ///
     /// pos: current position of the piece
     pub fn get_piece_moves(&mut self, pos: Position) -> Vec<Turn> {
+        let mut moves = MoveList::new();
+        let color = self.at_position(pos).expect("Piece not there").color;
+        let Some(masks) = self.legal_masks(color) else {
+            return vec![];
+        };
+        self.piece_moves(pos, &mut moves);
+        self.retain_legal(0, &masks, &mut moves);
+        moves.into()
+    }
+
+    /// Add the moves that the piece at the given square could make to the
+    /// given list, including those that would leave its king in check
+    pub(super) fn piece_moves(&self, pos: Position, moves: &mut MoveList) {
         let kind = self.at_position(pos).expect("Piece not there").kind;
         match kind {
-            PieceType::King => self.king_moves(pos),
-            PieceType::Queen => self.queen_moves(pos),
-            PieceType::Rook => self.rook_moves(pos),
-            PieceType::Bishop => self.bishop_moves(pos),
-            PieceType::Knight => self.knight_moves(pos),
-            PieceType::Pawn => self.pawn_moves(pos),
+            PieceType::King => self.king_moves(pos, moves),
+            PieceType::Queen => self.queen_moves(pos, moves),
+            PieceType::Rook => self.rook_moves(pos, moves),
+            PieceType::Bishop => self.bishop_moves(pos, moves),
+            PieceType::Knight => self.knight_moves(pos, moves),
+            PieceType::Pawn => self.pawn_moves(pos, moves),
         }
     }
 
@@ -138,190 +202,155 @@ impl Board {
         }
     }
 
-    fn add_move_if_legal(&mut self, turn: Turn, moves: &mut Vec<Turn>) {
-        if self.is_move_legal(turn.clone()) {
-            moves.push(turn);
-        }
+    /// Remove the turns from the given index onwards that would leave the
+    /// moving player's king in check
+    fn retain_legal(&mut self, first: usize, masks: &LegalMasks, moves: &mut MoveList) {
+        let mut index = 0;
+        moves.retain(|turn| {
+            index += 1;
+            if index <= first {
+                return true;
+            }
+            let start = self.stats_timer();
+            let legal = self.is_legal_with(turn, masks);
+            self.record_legality_check(start, legal);
+            legal
+        });
     }
 
-    /// Get moves in a line from the given directions
-    fn line_moves(&mut self, pos: Position, directions: &[(i8, i8)]) -> Vec<Turn> {
-        let mut moves = vec![];
-
-        for (r_off, c_off) in directions {
-            let mut new_pos = pos;
-            while let Some(off_pos) = new_pos.offset(*r_off, *c_off) {
-                new_pos = off_pos;
-                if let Some(turn) = self.get_turn_simple(pos, new_pos) {
-                    let was_capture = turn.capture.is_some();
-                    self.add_move_if_legal(turn, &mut moves);
-
-                    if was_capture {
-                        break;
-                    }
-                } else {
-                    break;
-                }
+    /// Get the moves to each of the given squares that isn't occupied by
+    /// the moving player's own pieces
+    fn target_moves(&self, pos: Position, targets: Bitboard, moves: &mut MoveList) {
+        let color = self.at_position(pos).unwrap().color;
+        for to in targets & !self.occupied_by(color) {
+            if let Some(turn) = self.get_turn_simple(pos, to) {
+                moves.push(turn);
             }
         }
-
-        moves
     }
 
-    fn rook_moves(&mut self, pos: Position) -> Vec<Turn> {
-        self.line_moves(pos, &[(1, 0), (0, 1), (-1, 0), (0, -1)])
+    fn rook_moves(&self, pos: Position, moves: &mut MoveList) {
+        self.target_moves(pos, rook_attacks(pos, self.occupied()), moves);
     }
 
-    fn bishop_moves(&mut self, pos: Position) -> Vec<Turn> {
-        self.line_moves(pos, &[(1, 1), (1, -1), (-1, -1), (-1, 1)])
+    fn bishop_moves(&self, pos: Position, moves: &mut MoveList) {
+        self.target_moves(pos, bishop_attacks(pos, self.occupied()), moves);
     }
 
-    fn queen_moves(&mut self, pos: Position) -> Vec<Turn> {
-        self.line_moves(
+    fn queen_moves(&self, pos: Position, moves: &mut MoveList) {
+        let occupied = self.occupied();
+        self.target_moves(
             pos,
-            &[
-                (1, 0),
-                (0, 1),
-                (-1, 0),
-                (0, -1),
-                (1, 1),
-                (1, -1),
-                (-1, -1),
-                (-1, 1),
-            ],
-        )
+            rook_attacks(pos, occupied) | bishop_attacks(pos, occupied),
+            moves,
+        );
     }
 
-    fn king_moves(&mut self, from_pos: Position) -> Vec<Turn> {
-        let mut moves = vec![];
-        for r in [-1, 0, 1] {
-            for c in [-1, 0, 1] {
-                if r != 0 || c != 0 {
-                    if let Some(to_pos) = from_pos.offset(r, c) {
-                        if let Some(turn) = self.get_turn_simple(from_pos, to_pos) {
-                            self.add_move_if_legal(turn, &mut moves);
-                        }
-                    }
-                }
-            }
-        }
+    fn king_moves(&self, from_pos: Position, moves: &mut MoveList) {
+        self.target_moves(from_pos, king_attacks(from_pos), moves);
         // Castling
-        // Can't have moved, and must be on the first rank
-        let piece = self.at_position(from_pos).unwrap();
-        if piece.move_count == 0 && from_pos.row() == piece.color.get_home() {
-            self.castling_moves(from_pos, &mut moves);
+        // Must still have the right to castle, and be on the first rank
+        let color = self.at_position(from_pos).unwrap().color;
+        if self.castling_rights().can_castle_any(color) && from_pos.row() == color.get_home() {
+            self.castling_moves(from_pos, moves);
         }
-        moves
     }
 
-    fn castling_moves(&mut self, from_pos: Position, moves: &mut Vec<Turn>) {
-        // Find the rooks
-        for (row, col, res_col) in [(0, 1, 6), (0, -1, 2)] {
-            // Check each square for pieces
-            let mut new_pos = from_pos;
-            while let Some(pos) = new_pos.offset(row, col) {
-                new_pos = pos;
-                if !self.castling_single_move(new_pos, from_pos, col, res_col, row, moves) {
-                    break;
-                }
+    /// Add the legal castling moves for the king at the given position
+    fn castling_moves(&self, from_pos: Position, moves: &mut MoveList) {
+        for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+            if let Ok(turn) = self.castling_turn(from_pos, side) {
+                moves.push(turn);
             }
         }
     }
 
-    /// Check a castling move, returning false if no more checks should be done
-    /// down this line
-    fn castling_single_move(
-        &mut self,
-        new_pos: Position,
+    /// Returns the turn castling the king at the given position on the given
+    /// side, or the reason it can't castle there.
+    ///
+    /// This supports Chess960, where the king and rooks may start on any
+    /// file, but always end up on the same squares as in standard chess.
+    /// Whether the king's destination is attacked is left to the legality
+    /// check, as the rook may have been blocking an attack on it.
+    pub(super) fn castling_turn(
+        &self,
         from_pos: Position,
-        col: i8,
-        res_col: i8,
-        row: i8,
-        moves: &mut Vec<Turn>,
-    ) -> bool {
-        // If it contains a piece
-        if let Some(other_piece) = self.at_position(new_pos) {
-            let this_piece = self.at_position(from_pos).unwrap();
-            // If it's our rook
-            if !(other_piece.kind == PieceType::Rook
-                && other_piece.color == this_piece.color
-                && other_piece.move_count == 0)
-            {
-                return false;
-            }
-
-            // We might be able to castle
-            // Check up to the resultant square that nothing is
-            // under attack
-            let from = from_pos.col() + col;
-            let to = res_col - col;
-            let start = i8::min(from, to);
-            let stop = i8::max(from, to);
-            for c in start..stop {
-                let pos = Position::new(row, c);
-                // If a piece is attacking this square, castling
-                // isn't allowed on this side
-                if self.are_pieces_attacking(pos, !this_piece.color) {
-                    return false;
-                }
+        side: CastlingSide,
+    ) -> Result<Turn, IllegalMoveReason> {
+        let color = self.at_position(from_pos).unwrap().color;
+        let row = from_pos.row();
+        let Some(rook_col) = self.castling_rights().rook_col(color, side) else {
+            return Err(IllegalMoveReason::CastlingNotAllowed(side));
+        };
+        let rook_pos = Position::new(row, rook_col);
+        // The rook must still be there, on the correct side of the king
+        if !matches!(
+            self.at_position(rook_pos),
+            Some(piece) if piece.kind == PieceType::Rook && piece.color == color
+        ) || (rook_col - from_pos.col()) * side.get_direction() <= 0
+        {
+            return Err(IllegalMoveReason::CastlingNotAllowed(side));
+        }
+        let king_to = Position::new(row, side.king_destination());
+        let rook_to = Position::new(row, side.rook_destination());
+
+        // Every square that the king and rook move through or to must be
+        // empty, other than the squares they start on
+        let cols = [from_pos.col(), rook_col, king_to.col(), rook_to.col()];
+        let start = *cols.iter().min().unwrap();
+        let stop = *cols.iter().max().unwrap();
+        for col in start..=stop {
+            let pos = Position::new(row, col);
+            if col != from_pos.col() && col != rook_col && self.at_position(pos).is_some() {
+                return Err(IllegalMoveReason::Blocked(pos));
             }
-
-            self.add_move_if_legal(
-                Turn::new_additional(
-                    this_piece.kind,
-                    (from_pos, Position::new(from_pos.row(), res_col)),
-                    (new_pos, Position::new(from_pos.row(), res_col - col)),
-                ),
-                moves,
-            );
         }
-        true
-    }
 
-    fn knight_moves(&mut self, pos: Position) -> Vec<Turn> {
-        let mut moves = vec![];
-
-        for (r, c) in KNIGHT_MOVES {
-            if let Some(to) = pos.offset(r, c) {
-                if let Some(turn) = self.get_turn_simple(pos, to) {
-                    self.add_move_if_legal(turn, &mut moves);
-                }
+        // Can't castle out of check, or through an attacked square
+        if self.are_pieces_attacking(from_pos, !color) {
+            return Err(IllegalMoveReason::CastlingOutOfCheck);
+        }
+        let direction = (king_to.col() - from_pos.col()).signum();
+        let mut col = from_pos.col() + direction;
+        while col != king_to.col() {
+            let pos = Position::new(row, col);
+            if self.are_pieces_attacking(pos, !color) {
+                return Err(IllegalMoveReason::CastlingThroughCheck(pos));
             }
+            col += direction;
         }
 
-        moves
+        Ok(Turn::new_additional(
+            PieceType::King,
+            (from_pos, king_to),
+            (rook_pos, rook_to),
+        ))
     }
 
-    fn pawn_moves(&mut self, pos: Position) -> Vec<Turn> {
-        let mut moves = vec![];
-
-        let color = self.at_position(pos).unwrap().color;
-
-        self.pawn_advance(pos, &mut moves);
-        self.pawn_capture(pos, -1, &mut moves);
-        self.pawn_capture(pos, 1, &mut moves);
-        self.pawn_en_passant(pos, &mut moves);
-
-        // 6th row, promotions
-        if pos.row() == color.get_home() + color.get_direction() * 6 {}
+    fn knight_moves(&self, pos: Position, moves: &mut MoveList) {
+        self.target_moves(pos, knight_attacks(pos), moves);
+    }
 
-        moves
+    fn pawn_moves(&self, pos: Position, moves: &mut MoveList) {
+        self.pawn_advance(pos, moves);
+        self.pawn_capture(pos, -1, moves);
+        self.pawn_capture(pos, 1, moves);
+        self.pawn_en_passant(pos, moves);
     }
 
-    fn pawn_advance(&mut self, pos: Position, moves: &mut Vec<Turn>) {
-        let piece = self.at_position(pos).unwrap().clone();
+    fn pawn_advance(&self, pos: Position, moves: &mut MoveList) {
+        let piece = *self.at_position(pos).unwrap();
         if let Some(pos_offset) = pos.offset(piece.color.get_direction(), 0) {
             if self.at_position(pos_offset).is_none() {
                 // Promotion
                 if pos_offset.row() == (!piece.color).get_home() {
                     for promo in PROMOTABLE_TYPES {
-                        self.add_move_if_legal(
-                            Turn::new_promotion(piece.kind, pos, pos_offset, promo, false),
-                            moves,
-                        );
+                        moves.push(Turn::new_promotion(
+                            piece.kind, pos, pos_offset, promo, false,
+                        ));
                     }
                 } else {
-                    self.add_move_if_legal(Turn::new_basic(piece.kind, pos, pos_offset), moves);
+                    moves.push(Turn::new_basic(piece.kind, pos, pos_offset));
                 }
                 // First move can be two spaces
                 if pos.row() == piece.color.get_home() + piece.color.get_direction() {
@@ -329,39 +358,34 @@ impl Board {
                         .offset(piece.color.get_direction(), 0)
                         .expect("Since they're at row 2, we should never leave the board");
                     if self.at_position(pos_offset).is_none() {
-                        self.add_move_if_legal(Turn::new_basic(piece.kind, pos, pos_offset), moves);
+                        moves.push(Turn::new_basic(piece.kind, pos, pos_offset));
                     }
                 }
             }
         }
     }
 
-    fn pawn_capture(&mut self, pos: Position, c_off: i8, moves: &mut Vec<Turn>) {
+    fn pawn_capture(&self, pos: Position, c_off: i8, moves: &mut MoveList) {
         let this_piece = self.at_position(pos).unwrap();
         if let Some(pos_offset) = pos.offset(this_piece.color.get_direction(), c_off) {
             if let Some(other_piece) = self.at_position(pos_offset) {
-                let other_kind = other_piece.kind;
+                let this_kind = this_piece.kind;
                 if this_piece.color == !other_piece.color {
                     // Promotion
                     if pos_offset.row() == other_piece.color.get_home() {
                         for promo in PROMOTABLE_TYPES {
-                            self.add_move_if_legal(
-                                Turn::new_promotion(other_kind, pos, pos_offset, promo, true),
-                                moves,
-                            );
+                            moves
+                                .push(Turn::new_promotion(this_kind, pos, pos_offset, promo, true));
                         }
                     } else {
-                        self.add_move_if_legal(
-                            Turn::new_capture(this_piece.kind, pos, pos_offset),
-                            moves,
-                        );
+                        moves.push(Turn::new_capture(this_piece.kind, pos, pos_offset));
                     }
                 }
             }
         }
     }
 
-    fn pawn_en_passant(&mut self, pos: Position, moves: &mut Vec<Turn>) {
+    fn pawn_en_passant(&self, pos: Position, moves: &mut MoveList) {
         let this_piece = self.at_position(pos).unwrap();
         // If there's an en passant target
         if let Some(target) = self.en_passant_target {
@@ -370,15 +394,12 @@ impl Board {
                 && (pos.col() - target.col()).abs() == 1
             {
                 // Holy hell
-                self.add_move_if_legal(
-                    Turn::new_capture_complex(
-                        this_piece.kind,
-                        pos,
-                        target,
-                        Position::new(pos.row(), target.col()),
-                    ),
-                    moves,
-                );
+                moves.push(Turn::new_capture_complex(
+                    this_piece.kind,
+                    pos,
+                    target,
+                    Position::new(pos.row(), target.col()),
+                ));
             }
         }
     }
@@ -0,0 +1,140 @@
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "std")]
+use rayon::prelude::*;
+
+use crate::game::MoveList;
+
+use super::Board;
+
+/// Counts of positions found by `Board::perft_cached`, so that positions
+/// reached by more than one sequence of turns are only counted once.
+///
+/// Each slot holds one result, and is overwritten by the next result that
+/// maps to it.
+#[derive(Debug, Clone)]
+pub struct PerftCache {
+    /// Zobrist hash, depth and count of each stored result
+    entries: Vec<Option<(u64, u32, u64)>>,
+}
+
+impl PerftCache {
+    /// Create a cache using roughly the given number of megabytes
+    pub fn new(megabytes: usize) -> Self {
+        let slots = megabytes * 1024 * 1024 / size_of::<Option<(u64, u32, u64)>>();
+        let slots = if slots == 0 { 1 } else { 1 << slots.ilog2() };
+        Self {
+            entries: vec![None; slots],
+        }
+    }
+
+    /// Remove every stored result
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+
+    fn index(&self, key: u64, depth: u32) -> usize {
+        // Mix in the depth, so that the same position at different depths
+        // doesn't always compete for one slot
+        (key ^ (depth as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)) as usize
+            & (self.entries.len() - 1)
+    }
+
+    fn get(&self, key: u64, depth: u32) -> Option<u64> {
+        match self.entries[self.index(key, depth)] {
+            Some((k, d, count)) if k == key && d == depth => Some(count),
+            _ => None,
+        }
+    }
+
+    fn insert(&mut self, key: u64, depth: u32, count: u64) {
+        let index = self.index(key, depth);
+        self.entries[index] = Some((key, depth, count));
+    }
+}
+
+impl Board {
+    /// Count the positions reachable in exactly `depth` turns, for checking
+    /// move generation against known results.
+    ///
+    /// Draws by repetition or the move limit are ignored, as is usual for
+    /// perft.
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let mut moves = MoveList::new();
+        self.generate_moves(&mut moves);
+        // Every legal move leads to exactly one position, so there's no need
+        // to make them
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        let mut count = 0;
+        for turn in moves {
+            self.make_turn(turn);
+            count += self.perft(depth - 1);
+            self.undo_turn();
+        }
+        count
+    }
+
+    /// Count the positions reachable in exactly `depth` turns, like `perft`,
+    /// but splitting the turns from this position between threads, each
+    /// with its own board in this position.
+    ///
+    /// This needs the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn perft_parallel(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        // The history isn't needed, so don't copy it to each thread
+        let state = self.state();
+        let mut moves = MoveList::new();
+        Board::from_state(state).generate_moves(&mut moves);
+        Vec::from(moves)
+            .into_par_iter()
+            .map(|turn| {
+                let mut board = Board::from_state(state);
+                board.make_turn(turn);
+                board.perft(depth - 1)
+            })
+            .sum()
+    }
+}
+
+impl Board {
+    /// Count the positions reachable in exactly `depth` turns, like `perft`,
+    /// but looking up positions that have already been counted in the given
+    /// cache, which makes deep counts much faster.
+    ///
+    /// The cache may be reused between calls, as long as it is only used
+    /// with perft.
+    pub fn perft_cached(&mut self, depth: u32, cache: &mut PerftCache) -> u64 {
+        if depth <= 1 {
+            return self.perft(depth);
+        }
+        let key = self.zobrist_hash();
+        if let Some(count) = cache.get(key, depth) {
+            return count;
+        }
+        let mut moves = MoveList::new();
+        self.generate_moves(&mut moves);
+        let mut count = 0;
+        for turn in moves {
+            self.make_turn(turn);
+            count += self.perft_cached(depth - 1, cache);
+            self.undo_turn();
+        }
+        cache.insert(key, depth, count);
+        count
+    }
+}
+
+/// Count the positions reachable from the given board in exactly `depth`
+/// turns, for checking move generation against known results.
+///
+/// This is the same as `Board::perft`.
+pub fn perft(board: &mut Board, depth: u32) -> u64 {
+    board.perft(depth)
+}
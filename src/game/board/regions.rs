@@ -0,0 +1,53 @@
+use crate::game::{king_attacks, Bitboard, Color, PieceType, Position};
+
+use super::Board;
+
+impl Board {
+    /// Returns the positions of all pieces on the given file (column, 0-7)
+    pub fn pieces_on_file(&self, col: i8) -> Bitboard {
+        self.occupied() & Bitboard::file(col)
+    }
+
+    /// Returns the positions of all pieces on the given rank (row, 0-7)
+    pub fn pieces_on_rank(&self, row: i8) -> Bitboard {
+        self.occupied() & Bitboard::rank(row)
+    }
+
+    /// Returns the positions of all pieces on the diagonal running in the
+    /// direction of A1 to H8 through the given position
+    pub fn pieces_on_diagonal(&self, through: Position) -> Bitboard {
+        self.occupied() & Bitboard::diagonal(through)
+    }
+
+    /// Returns the positions of all pieces on the diagonal running in the
+    /// direction of A8 to H1 through the given position
+    pub fn pieces_on_anti_diagonal(&self, through: Position) -> Bitboard {
+        self.occupied() & Bitboard::anti_diagonal(through)
+    }
+
+    /// Returns the squares making up the zone around the king of the given
+    /// color: the king's square and every square next to it, or no squares
+    /// if they have no king
+    pub fn king_zone(&self, color: Color) -> Bitboard {
+        match self.find_king(color) {
+            Some(king) => king_attacks(king) | Bitboard::from_position(king),
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    /// Returns the positions of all pieces, of either color, in the zone
+    /// around the king of the given color
+    pub fn pieces_in_king_zone(&self, color: Color) -> Bitboard {
+        self.occupied() & self.king_zone(color)
+    }
+
+    /// Returns the positions of the pawns of `pawn_color` on the same file as
+    /// the given position and in front of it, where `forwards` gives whose
+    /// direction of movement counts as in front
+    ///
+    /// For example, the pawns blocking a white pawn are given by
+    /// `pawns_in_front(pos, Color::White, Color::Black)`.
+    pub fn pawns_in_front(&self, from: Position, forwards: Color, pawn_color: Color) -> Bitboard {
+        self.pieces(PieceType::Pawn, pawn_color) & Bitboard::forward_span(from, forwards)
+    }
+}
@@ -0,0 +1,60 @@
+use super::Board;
+
+impl Board {
+    /// Replace the hash of the current position, after it was changed
+    /// without making a turn
+    pub(super) fn refresh_position_hash(&mut self) {
+        self.cache.clear();
+        let hash = self.zobrist_hash();
+        match self.position_hashes.last_mut() {
+            Some(last) => *last = hash,
+            None => self.position_hashes.push(hash),
+        }
+    }
+
+    /// Returns the number of times the current position has occurred,
+    /// including now
+    pub fn repetition_count(&self) -> usize {
+        let current = *self.position_hashes.last().unwrap();
+        // Positions can't repeat across a capture, pawn push or loss of
+        // castling rights, so only look back as far as the last of those,
+        // and only at positions with the same player to move
+        let start = self.irreversible_plies.last().copied().unwrap_or(0);
+        self.position_hashes[start..]
+            .iter()
+            .rev()
+            .step_by(2)
+            .filter(|hash| **hash == current)
+            .count()
+    }
+
+    /// Returns whether the current position has occurred before, which a
+    /// search can treat as a draw, as the player who repeated it could do so
+    /// again.
+    ///
+    /// This stops at the first earlier occurrence, so is cheaper than
+    /// `repetition_count`, and only looks back as far as the last turn that
+    /// can't be reversed.
+    pub fn is_repetition(&self) -> bool {
+        let current = *self.position_hashes.last().unwrap();
+        let start = self.irreversible_plies.last().copied().unwrap_or(0);
+        self.position_hashes[start..]
+            .iter()
+            .rev()
+            .skip(2)
+            .step_by(2)
+            .any(|hash| *hash == current)
+    }
+
+    /// Returns whether the position is a draw by threefold repetition
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetition_count() >= 3
+    }
+
+    /// Returns whether the position is a draw by fivefold repetition, which
+    /// unlike threefold repetition ends the game without needing to be
+    /// claimed
+    pub fn is_fivefold_repetition(&self) -> bool {
+        self.repetition_count() >= 5
+    }
+}
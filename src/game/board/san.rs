@@ -0,0 +1,335 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{error::Error, fmt::Write};
+
+use crate::game::{piece::PROMOTABLE_TYPES, Color, PgnResult, PieceType, Position, Turn};
+
+use super::Board;
+
+/// Error when finding the turn described by a SAN move
+#[derive(Debug, Clone)]
+pub enum SanError {
+    /// The move isn't valid SAN
+    /// Includes the given move
+    Invalid(String),
+
+    /// The move is valid SAN, but no legal turn matches it
+    /// Includes the given move
+    Illegal(String),
+
+    /// More than one legal turn matches the move
+    /// Includes all the matching turns
+    Ambiguous(Vec<Turn>),
+}
+
+impl core::fmt::Display for SanError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SanError::Invalid(san) => write!(f, "'{}' isn't a valid SAN move", san),
+            SanError::Illegal(san) => write!(f, "'{}' isn't a legal move", san),
+            SanError::Ambiguous(turns) => {
+                write!(f, "move is ambiguous, it could be any of")?;
+                for turn in turns {
+                    write!(f, "\n- {}", turn)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for SanError {}
+
+impl Board {
+    /// Returns the standard algebraic notation (SAN) for a turn, such as
+    /// `Nbd2`, `exd5`, `e8=Q+` or `O-O`.
+    ///
+    /// The turn must be legal in the current position.
+    pub fn turn_to_san(&mut self, turn: &Turn) -> String {
+        let mut san = String::new();
+
+        if let Some((rook_from, _)) = turn.additional_move {
+            // Castling
+            if rook_from.col() > turn.from.col() {
+                san.push_str("O-O");
+            } else {
+                san.push_str("O-O-O");
+            }
+        } else if turn.kind == PieceType::Pawn {
+            if turn.capture.is_some() {
+                san.push(turn.from.file().to_ascii_lowercase());
+                san.push('x');
+            }
+            san.push_str(&turn.to.to_string().to_ascii_lowercase());
+            if let Some(promo) = turn.promote_to {
+                san.push('=');
+                san.push(promo.letter());
+            }
+        } else {
+            san.push(turn.kind.letter());
+            san.push_str(&self.disambiguate(turn));
+            if turn.capture.is_some() {
+                san.push('x');
+            }
+            san.push_str(&turn.to.to_string().to_ascii_lowercase());
+        }
+
+        // Check and checkmate
+        self.make_turn(*turn);
+        if self.is_check() {
+            if !self.has_legal_move() {
+                san.push('#');
+            } else {
+                san.push('+');
+            }
+        }
+        self.undo_turn();
+
+        san
+    }
+
+    /// Returns the part of a SAN move needed to tell apart pieces of the same
+    /// kind that could move to the same square
+    fn disambiguate(&mut self, turn: &Turn) -> String {
+        let others: Vec<Turn> = self
+            .do_get_moves()
+            .into_iter()
+            .filter(|other| {
+                other.kind == turn.kind
+                    && other.to == turn.to
+                    && other.from != turn.from
+                    && other.additional_move.is_none()
+            })
+            .collect();
+
+        let file = turn.from.file().to_ascii_lowercase();
+        let rank = turn.from.rank();
+        if others.is_empty() {
+            String::new()
+        } else if others
+            .iter()
+            .all(|other| other.from.col() != turn.from.col())
+        {
+            file.to_string()
+        } else if others
+            .iter()
+            .all(|other| other.from.row() != turn.from.row())
+        {
+            rank.to_string()
+        } else {
+            format!("{}{}", file, rank)
+        }
+    }
+
+    /// Write the move history as numbered SAN, such as `1. e4 e5 2. Nf3`,
+    /// followed by the result if the game is over, such as `1. f3 e5 2. g4
+    /// Qh4# 0-1`, without any PGN headers.
+    ///
+    /// To write into an `io::Write`, use `write!` with `Board::movetext`.
+    pub fn write_movetext<W: Write>(&self, w: &mut W) -> core::fmt::Result {
+        self.write_turns(w)?;
+        let result = PgnResult::from(&self.clone().get_game_state());
+        match (result, self.moves.is_empty()) {
+            (PgnResult::Unknown, _) => Ok(()),
+            (result, true) => write!(w, "{}", result),
+            (result, false) => write!(w, " {}", result),
+        }
+    }
+
+    /// Write the move history as numbered SAN, without a result
+    pub(crate) fn write_turns<W: Write>(&self, w: &mut W) -> core::fmt::Result {
+        // Rewind a copy of the board to the start of the game, so that each
+        // turn can be described in the position it was made from. An
+        // outcome from the players would stop the turns from being legal.
+        let mut board = self.clone();
+        board.resume();
+        let mut turns = vec![];
+        while let Some(turn) = board.undo_turn() {
+            turns.push(turn);
+        }
+
+        for (i, turn) in turns.into_iter().rev().enumerate() {
+            if i > 0 {
+                w.write_char(' ')?;
+            }
+            match board.whose_turn() {
+                Color::White => write!(w, "{}. ", board.num_moves)?,
+                Color::Black if i == 0 => write!(w, "{}... ", board.num_moves)?,
+                Color::Black => {}
+            }
+            w.write_str(&board.turn_to_san(&turn))?;
+            board.make_turn(turn);
+        }
+        Ok(())
+    }
+
+    /// Returns the move history as numbered SAN, such as `1. e4 e5 2. Nf3`,
+    /// followed by the result if the game is over, without any PGN headers
+    pub fn movetext(&self) -> String {
+        let mut movetext = String::new();
+        self.write_movetext(&mut movetext)
+            .expect("Writing to a String doesn't fail");
+        movetext
+    }
+
+    /// Find the legal turn described by a move in standard algebraic
+    /// notation (SAN), such as `Nbd2`, `exd5`, `e8=Q+` or `O-O`.
+    ///
+    /// Check markers and annotations (`+`, `#`, `!`, `?`) are ignored, and
+    /// unnecessary disambiguation is accepted. If the move could refer to
+    /// more than one legal turn, such as `Nd2` when both knights can reach
+    /// d2, all of the matching turns are given in the error so that the user
+    /// can be asked which they meant.
+    pub fn resolve_san(&mut self, san: &str) -> Result<Turn, SanError> {
+        let invalid = || SanError::Invalid(san.to_string());
+        let illegal = || SanError::Illegal(san.to_string());
+        let stripped = san.trim_end_matches(['+', '#', '!', '?']);
+        let moves = self.do_get_moves();
+
+        // Castling
+        if let Some(kingside) = match stripped {
+            "O-O" | "0-0" => Some(true),
+            "O-O-O" | "0-0-0" => Some(false),
+            _ => None,
+        } {
+            return moves
+                .into_iter()
+                .find(|turn| match turn.additional_move {
+                    Some((rook_from, _)) => (rook_from.col() > turn.from.col()) == kingside,
+                    None => false,
+                })
+                .ok_or_else(illegal);
+        }
+
+        let mut chars: Vec<char> = stripped.chars().collect();
+
+        // Promotion, with or without an `=`
+        let mut promote_to = None;
+        if let Some(&last) = chars.last() {
+            if let Some(promo) = PROMOTABLE_TYPES.into_iter().find(|p| p.letter() == last) {
+                promote_to = Some(promo);
+                chars.pop();
+                if chars.last() == Some(&'=') {
+                    chars.pop();
+                }
+            }
+        }
+
+        // Destination square
+        if chars.len() < 2 {
+            return Err(invalid());
+        }
+        let to: Position = chars
+            .split_off(chars.len() - 2)
+            .iter()
+            .collect::<String>()
+            .parse()
+            .map_err(|_| invalid())?;
+        if chars.last() == Some(&'x') {
+            chars.pop();
+        }
+
+        // Kind of piece, which is omitted for pawns
+        let kind = match chars.first() {
+            Some(&c) if c.is_ascii_uppercase() => {
+                chars.remove(0);
+                [
+                    PieceType::King,
+                    PieceType::Queen,
+                    PieceType::Rook,
+                    PieceType::Bishop,
+                    PieceType::Knight,
+                ]
+                .into_iter()
+                .find(|kind| kind.letter() == c)
+                .ok_or_else(invalid)?
+            }
+            _ => PieceType::Pawn,
+        };
+
+        // Anything left is disambiguation
+        let mut from_col = None;
+        let mut from_row = None;
+        for c in chars {
+            match c {
+                'a'..='h' if from_col.is_none() => from_col = Some((c as u8 - b'a') as i8),
+                '1'..='8' if from_row.is_none() => from_row = Some((c as u8 - b'1') as i8),
+                _ => return Err(invalid()),
+            }
+        }
+
+        let mut matching: Vec<Turn> = moves
+            .into_iter()
+            .filter(|turn| {
+                turn.kind == kind
+                    && turn.to == to
+                    && turn.additional_move.is_none()
+                    && turn.promote_to == promote_to
+                    && from_col.is_none_or(|col| turn.from.col() == col)
+                    && from_row.is_none_or(|row| turn.from.row() == row)
+            })
+            .collect();
+        match matching.len() {
+            0 => Err(illegal()),
+            1 => Ok(matching.pop().unwrap()),
+            _ => Err(SanError::Ambiguous(matching)),
+        }
+    }
+
+    /// Find the legal turn described by a move in standard algebraic
+    /// notation (SAN), such as `Nbd2`, `exd5`, `e8=Q+` or `O-O`.
+    ///
+    /// Returns `None` if the move is malformed, illegal, or ambiguous. Use
+    /// `resolve_san` to find out why.
+    pub fn parse_san(&mut self, san: &str) -> Option<Turn> {
+        self.resolve_san(san).ok()
+    }
+
+    /// Play a sequence of SAN moves, such as `1. e4 e5 2. Nf3`.
+    ///
+    /// Move numbers and result tokens are ignored. If a move can't be
+    /// played, the board is left unchanged and the index of the offending
+    /// whitespace-separated token is returned.
+    pub fn apply_movetext(&mut self, movetext: &str) -> Result<(), usize> {
+        let mut played = 0;
+        for (i, token) in movetext.split_whitespace().enumerate() {
+            if matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*") {
+                continue;
+            }
+            let san = strip_move_number(token);
+            if san.is_empty() {
+                continue;
+            }
+            match self.parse_san(san) {
+                Some(turn) => {
+                    self.make_turn(turn);
+                    played += 1;
+                }
+                None => {
+                    for _ in 0..played {
+                        self.undo_turn();
+                    }
+                    return Err(i);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Strip a move number such as `12.` or `12...` from the start of a token,
+/// which may be attached to the move itself. Digits are only part of a move
+/// number if they are followed by a dot, so castling written as `0-0` is
+/// kept.
+fn strip_move_number(token: &str) -> &str {
+    let digits = token.trim_start_matches(|c: char| c.is_ascii_digit());
+    if digits.len() < token.len() && digits.starts_with('.') {
+        digits.trim_start_matches('.')
+    } else {
+        token
+    }
+}
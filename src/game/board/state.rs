@@ -0,0 +1,119 @@
+use crate::game::{
+    bitboard::Bitboard, piece::Piece, zobrist, CastlingRights, Color, PieceType, Position,
+};
+use alloc::vec;
+
+use super::Board;
+
+/// The current position of a board, without any of its history.
+///
+/// This is `Copy`, so it's cheap to save and restore, or to hand to other
+/// threads, whereas cloning a `Board` also copies every turn made so far.
+/// Convert it back to a board with `Board::from_state` to make turns from
+/// it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardState {
+    squares: [Option<Piece>; 64],
+    pieces: [Bitboard; 6],
+    colors: [Bitboard; 2],
+    kings: [Option<Position>; 2],
+    material: [u32; 2],
+    piece_hash: u64,
+    pawn_hash: u64,
+    whose_turn: Color,
+    castling_rights: CastlingRights,
+    en_passant_target: Option<Position>,
+    halfmove_clock: i32,
+    fullmove_number: i32,
+}
+
+impl BoardState {
+    /// Returns the kind and color of the piece at the given position, if
+    /// there is one
+    pub fn at_position(&self, pos: Position) -> Option<(PieceType, Color)> {
+        self.squares[pos.pos()].map(|piece| (piece.kind, piece.color))
+    }
+
+    /// Returns the squares containing pieces of the given kind and color
+    pub fn pieces(&self, kind: PieceType, color: Color) -> Bitboard {
+        self.pieces[kind.index()] & self.colors[color.index()]
+    }
+
+    pub fn whose_turn(&self) -> Color {
+        self.whose_turn
+    }
+
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling_rights
+    }
+
+    pub fn en_passant_target(&self) -> Option<Position> {
+        self.en_passant_target
+    }
+
+    pub fn halfmove_clock(&self) -> u32 {
+        self.halfmove_clock as u32
+    }
+
+    pub fn fullmove_number(&self) -> u32 {
+        self.fullmove_number as u32
+    }
+
+    /// Returns the Zobrist hash of the position, which matches
+    /// `Board::zobrist_hash` for the board it was taken from
+    pub fn zobrist_hash(&self) -> u64 {
+        self.piece_hash
+            ^ zobrist::state_key(
+                self.whose_turn,
+                self.castling_rights,
+                self.en_passant_target,
+            )
+    }
+}
+
+impl Board {
+    /// Returns the current position, without the turns leading up to it
+    pub fn state(&self) -> BoardState {
+        BoardState {
+            squares: self.squares,
+            pieces: self.pieces,
+            colors: self.colors,
+            kings: self.kings,
+            material: self.material,
+            piece_hash: self.piece_hash,
+            pawn_hash: self.pawn_hash,
+            whose_turn: self.whose_turn,
+            castling_rights: self.castling_rights(),
+            en_passant_target: self.en_passant_target,
+            halfmove_clock: *self.half_move_clock.last().unwrap(),
+            fullmove_number: self.num_moves,
+        }
+    }
+
+    /// Create a board in the given position, with no turns having been made
+    pub fn from_state(state: BoardState) -> Self {
+        let mut board = Self {
+            squares: state.squares,
+            pieces: state.pieces,
+            colors: state.colors,
+            kings: state.kings,
+            material: state.material,
+            piece_hash: state.piece_hash,
+            pawn_hash: state.pawn_hash,
+            whose_turn: state.whose_turn,
+            castling_rights: vec![state.castling_rights],
+            en_passant_target: state.en_passant_target,
+            half_move_clock: vec![state.halfmove_clock],
+            num_moves: state.fullmove_number,
+            ..Default::default()
+        };
+        board.refresh_position_hash();
+        board
+    }
+}
+
+impl From<BoardState> for Board {
+    fn from(state: BoardState) -> Self {
+        Board::from_state(state)
+    }
+}
@@ -0,0 +1,176 @@
+use core::{fmt::Display, time::Duration};
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::game::{piece::PIECE_TYPES, PieceType, Turn};
+
+use super::Board;
+
+/// Statistics about move generation and search, for guiding performance
+/// work.
+///
+/// These are only collected once enabled with `Board::enable_stats`. Times
+/// need a clock, so are always zero without the `std` feature.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    /// Number of turns made, which is the number of nodes visited by a
+    /// search
+    pub nodes: u64,
+
+    /// Number of times the legal moves of a position were generated
+    pub generations: u64,
+
+    /// Number of legal moves generated, indexed by `PieceType::index`
+    moves_generated: [u64; 6],
+
+    /// Number of legal captures generated
+    pub captures_generated: u64,
+
+    /// Number of candidate moves checked for legality
+    pub legality_checks: u64,
+
+    /// Number of candidate moves found to be illegal
+    pub illegal_moves: u64,
+
+    /// Total time spent generating moves, including legality checks
+    pub generation_time: Duration,
+
+    /// Time spent checking candidate moves for legality
+    pub legality_time: Duration,
+
+    /// Number of transposition table lookups, as recorded with
+    /// `Board::record_tt_probe`
+    pub tt_probes: u64,
+
+    /// Number of transposition table lookups that found an entry
+    pub tt_hits: u64,
+}
+
+impl Stats {
+    /// Returns the number of legal moves generated for the given kind of
+    /// piece
+    pub fn moves_generated(&self, kind: PieceType) -> u64 {
+        self.moves_generated[kind.index()]
+    }
+
+    /// Returns the number of legal moves generated for all pieces
+    pub fn total_moves_generated(&self) -> u64 {
+        self.moves_generated.iter().sum()
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "Nodes: {}", self.nodes)?;
+        writeln!(f, "Generations: {}", self.generations)?;
+        writeln!(f, "Moves generated: {}", self.total_moves_generated())?;
+        for kind in PIECE_TYPES {
+            writeln!(f, "- {}: {}", kind, self.moves_generated(kind))?;
+        }
+        writeln!(f, "Captures generated: {}", self.captures_generated)?;
+        writeln!(
+            f,
+            "Legality checks: {} ({} illegal)",
+            self.legality_checks, self.illegal_moves
+        )?;
+        writeln!(f, "Generation time: {:?}", self.generation_time)?;
+        writeln!(f, "Legality check time: {:?}", self.legality_time)?;
+        writeln!(f, "TT hits: {} of {}", self.tt_hits, self.tt_probes)?;
+        Ok(())
+    }
+}
+
+impl Board {
+    /// Start collecting statistics, clearing any that were already
+    /// collected
+    pub fn enable_stats(&mut self) {
+        self.stats = Some(Stats::default());
+    }
+
+    /// Stop collecting statistics, returning the collected statistics
+    pub fn disable_stats(&mut self) -> Option<Stats> {
+        self.stats.take()
+    }
+
+    /// Set the statistics collected so far back to zero, if they are
+    /// enabled
+    pub fn reset_stats(&mut self) {
+        if let Some(stats) = &mut self.stats {
+            *stats = Stats::default();
+        }
+    }
+
+    /// Returns the statistics collected so far, if they are enabled
+    pub fn stats(&self) -> Option<&Stats> {
+        self.stats.as_ref()
+    }
+
+    /// Record a lookup of this position in a transposition table, and
+    /// whether it found an entry
+    pub fn record_tt_probe(&mut self, hit: bool) {
+        if let Some(stats) = &mut self.stats {
+            stats.tt_probes += 1;
+            if hit {
+                stats.tt_hits += 1;
+            }
+        }
+    }
+
+    /// Record a turn being made
+    pub(super) fn record_node(&mut self) {
+        if let Some(stats) = &mut self.stats {
+            stats.nodes += 1;
+        }
+    }
+
+    /// Record the legal moves produced by a call to `generate_moves`
+    pub(super) fn record_generation<'a>(
+        &mut self,
+        start: Option<Instant>,
+        turns: impl Iterator<Item = &'a Turn>,
+    ) {
+        if let (Some(stats), Some(start)) = (&mut self.stats, start) {
+            stats.generations += 1;
+            for turn in turns {
+                stats.moves_generated[turn.kind.index()] += 1;
+                if turn.capture.is_some() {
+                    stats.captures_generated += 1;
+                }
+            }
+            stats.generation_time += start.elapsed();
+        }
+    }
+
+    /// Record the result of checking a candidate move for legality
+    pub(super) fn record_legality_check(&mut self, start: Option<Instant>, legal: bool) {
+        if let (Some(stats), Some(start)) = (&mut self.stats, start) {
+            stats.legality_checks += 1;
+            if !legal {
+                stats.illegal_moves += 1;
+            }
+            stats.legality_time += start.elapsed();
+        }
+    }
+
+    /// Returns the current time if statistics are being collected
+    pub(super) fn stats_timer(&self) -> Option<Instant> {
+        self.stats.as_ref().map(|_| Instant::now())
+    }
+}
+
+/// Stand-in for `std::time::Instant` when there is no clock, which never
+/// measures any time passing
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Instant;
+
+#[cfg(not(feature = "std"))]
+impl Instant {
+    fn now() -> Self {
+        Instant
+    }
+
+    fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
@@ -1,17 +1,101 @@
-use crate::game::{Position, PieceType, Turn, Color};
+use core::{error::Error, fmt::Display};
+
+use crate::game::{Color, PieceType, Position, Turn};
 
 use super::Board;
 
+/// Error when trying to make a turn that can't be made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    /// The game is over, so no more turns can be made
+    GameOver,
+
+    /// There is no piece at the position being moved from
+    NoPiece(Position),
+
+    /// The piece at the position being moved from belongs to the player
+    /// whose turn it isn't
+    NotYourPiece(Position),
+
+    /// The kind of piece given in the turn isn't the kind of piece being
+    /// moved
+    WrongPiece(PieceType),
+
+    /// The turn isn't one of the legal turns in this position
+    Illegal,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MoveError::GameOver => write!(f, "the game is over"),
+            MoveError::NoPiece(pos) => write!(f, "there is no piece at {}", pos),
+            MoveError::NotYourPiece(pos) => {
+                write!(f, "the piece at {} belongs to the other player", pos)
+            }
+            MoveError::WrongPiece(kind) => write!(f, "the piece being moved is a {}", kind),
+            MoveError::Illegal => write!(f, "the move isn't legal"),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
 impl Board {
+    /// Make a turn, after checking that it is one of the legal turns in the
+    /// current position
+    ///
+    /// Unlike `make_turn`, this won't panic or corrupt the board if given a
+    /// bad turn, so should be used for turns from untrusted sources.
+    pub fn try_make_turn(&mut self, turn: Turn) -> Result<(), MoveError> {
+        let piece = self
+            .at_position(turn.from)
+            .ok_or(MoveError::NoPiece(turn.from))?;
+        if piece.color != self.whose_turn {
+            return Err(MoveError::NotYourPiece(turn.from));
+        }
+        if piece.kind != turn.kind {
+            return Err(MoveError::WrongPiece(piece.kind));
+        }
+        let moves = self.get_moves();
+        if moves.is_empty() {
+            return Err(MoveError::GameOver);
+        }
+        if !moves.contains(&turn) {
+            return Err(MoveError::Illegal);
+        }
+        self.make_turn(turn);
+        Ok(())
+    }
+
     /// Make a turn
-    /// It is assumed that the move is legal
+    /// It is assumed that the move is legal, see `try_make_turn` for turns
+    /// that haven't been checked
     pub fn make_turn(&mut self, turn: Turn) {
+        self.cache.clear();
+        self.record_node();
+        // Moving the king or a castling rook, or capturing a castling rook,
+        // loses those castling rights
+        let mut castling = self.castling_rights();
+        if turn.kind == PieceType::King {
+            castling.remove(self.whose_turn);
+        }
+        castling.remove_rook(self.whose_turn, turn.from);
+        if let Some(capture) = turn.capture {
+            castling.remove_rook(!self.whose_turn, capture);
+        }
+        let irreversible = turn.capture.is_some()
+            || turn.kind == PieceType::Pawn
+            || castling != self.castling_rights();
+        self.castling_rights.push(castling);
+        self.en_passant_history.push(self.en_passant_target);
+
         // If a piece is captured, remove it
         if let Some(capture) = turn.capture {
-            let captured = std::mem::replace(&mut self.squares[capture.pos()], None)
+            let captured = self
+                .take_piece(capture)
                 .expect("Capture non-existent piece");
             self.captures.push(captured);
-            self.squares[capture.pos()] = None;
             self.half_move_clock.push(-1);
         }
         // If it's a pawn push, but not a capture, record that
@@ -30,14 +114,13 @@ impl Board {
             self.en_passant_target = None;
         }
         // Lift the main piece
-        let mut piece = std::mem::replace(&mut self.squares[turn.from.pos()], None)
-            .expect("Move non-existent piece");
+        let mut piece = self.take_piece(turn.from).expect("Move non-existent piece");
         // Lift and place the second piece
         if let Some((from, to)) = turn.additional_move {
-            let secondary_piece = std::mem::replace(&mut self.squares[from.pos()], None)
+            let secondary_piece = self
+                .take_piece(from)
                 .expect("Non-existent additional piece");
-            assert!(self.squares[to.pos()].is_none());
-            self.squares[to.pos()] = Some(secondary_piece);
+            self.put_piece(to, secondary_piece);
         }
 
         // If the piece is promoting, make that adjustment
@@ -45,12 +128,8 @@ impl Board {
             piece.kind = promo_kind;
         }
 
-        // Increment that piece's move count
-        piece.move_count += 1;
-
         // Now place the main piece into the correct square
-        assert!(self.squares[turn.to.pos()].is_none(), "{}\n{}", self, turn);
-        self.squares[turn.to.pos()] = Some(piece);
+        self.put_piece(turn.to, piece);
 
         // And store the turn into the turn history and change whose turn it is
         *self.half_move_clock.last_mut().unwrap() += 1;
@@ -59,25 +138,36 @@ impl Board {
         if self.whose_turn == Color::White {
             self.num_moves += 1;
         }
+        if irreversible {
+            self.irreversible_plies.push(self.position_hashes.len());
+        }
+        self.position_hashes.push(self.zobrist_hash());
     }
 
     /// Undo the last turn
     /// Return it, or None if there is nothing to undo
     pub fn undo_turn(&mut self) -> Option<Turn> {
         let turn = self.moves.pop()?;
+        self.cache.clear();
+        self.castling_rights.pop();
+        self.position_hashes.pop();
+        if self.irreversible_plies.last() == Some(&self.position_hashes.len()) {
+            self.irreversible_plies.pop();
+        }
         // Lift piece from the expected place
-        let mut piece = std::mem::replace(&mut self.squares[turn.to.pos()], None)
+        let mut piece = self
+            .take_piece(turn.to)
             .expect("Undo move non-existent piece");
         // Lift and place the second piece
         if let Some((from, to)) = turn.additional_move {
-            let secondary_piece = std::mem::replace(&mut self.squares[to.pos()], None)
-                .expect("Non-existent additional piece");
-            self.squares[from.pos()] = Some(secondary_piece);
+            let secondary_piece = self.take_piece(to).expect("Non-existent additional piece");
+            self.put_piece(from, secondary_piece);
         }
 
         // Add back any captured piece
         if let Some(capture) = turn.capture {
-            self.squares[capture.pos()] = self.captures.pop();
+            let captured = self.captures.pop().expect("Restore non-existent capture");
+            self.put_piece(capture, captured);
         }
 
         // If the piece promoted, make that adjustment
@@ -85,28 +175,14 @@ impl Board {
             piece.kind = promo_from;
         }
 
-        // Decrement that piece's move count
-        piece.move_count -= 1;
-
         // Place the main piece and change whose turn it is
-        self.squares[turn.from.pos()] = Some(piece);
+        self.put_piece(turn.from, piece);
         self.whose_turn = !self.whose_turn;
 
-        // Check the move before this to handle the en passant target
-        if let Some(prev_turn) = self.moves.last() {
-            if prev_turn.kind == PieceType::Pawn
-                && (prev_turn.to.row() - prev_turn.from.row()).abs() == 2
-            {
-                self.en_passant_target = Some(Position::new(
-                    (prev_turn.to.row() + prev_turn.from.row()) / 2,
-                    prev_turn.from.col(),
-                ));
-            } else {
-                self.en_passant_target = None;
-            }
-        } else {
-            self.en_passant_target = None;
-        }
+        self.en_passant_target = self
+            .en_passant_history
+            .pop()
+            .expect("Restore non-existent en passant target");
 
         if self.half_move_clock.last() == Some(&0) {
             self.half_move_clock.pop();
@@ -119,4 +195,50 @@ impl Board {
 
         Some(turn)
     }
+
+    /// Pass the turn to the other player without moving a piece, as used by
+    /// engines for null move pruning.
+    ///
+    /// This clears the en passant target and counts towards the half-move
+    /// clock. It shouldn't be made while in check, as the king could then be
+    /// captured.
+    pub fn make_null_move(&mut self) {
+        self.cache.clear();
+        self.null_moves
+            .push((self.moves.len(), self.en_passant_target.take()));
+        *self.half_move_clock.last_mut().unwrap() += 1;
+        self.whose_turn = !self.whose_turn;
+        if self.whose_turn == Color::White {
+            self.num_moves += 1;
+        }
+        // Positions from before passing aren't repeated by the line after it
+        self.irreversible_plies.push(self.position_hashes.len());
+        self.position_hashes.push(self.zobrist_hash());
+    }
+
+    /// Undo the last null move, if it was made after the last turn.
+    /// Returns whether a null move was undone
+    pub fn undo_null_move(&mut self) -> bool {
+        if !self.is_null_move_last() {
+            return false;
+        }
+        self.cache.clear();
+        let (_, en_passant_target) = self.null_moves.pop().unwrap();
+        self.en_passant_target = en_passant_target;
+        *self.half_move_clock.last_mut().unwrap() -= 1;
+        if self.whose_turn == Color::White {
+            self.num_moves -= 1;
+        }
+        self.whose_turn = !self.whose_turn;
+        self.position_hashes.pop();
+        self.irreversible_plies.pop();
+        true
+    }
+
+    /// Returns whether a null move has been made since the last turn
+    fn is_null_move_last(&self) -> bool {
+        self.null_moves
+            .last()
+            .is_some_and(|(turns, _)| *turns == self.moves.len())
+    }
 }
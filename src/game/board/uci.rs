@@ -0,0 +1,49 @@
+use crate::game::{piece::PROMOTABLE_TYPES, Turn};
+use alloc::{format, string::String};
+
+use super::Board;
+
+impl Board {
+    /// Returns the UCI long algebraic notation for a turn, such as `e2e4` or
+    /// `e7e8q`. Castling is written as the king's move, such as `e1g1`,
+    /// unless the king or rook didn't start where they do in standard chess,
+    /// in which case it is written as the king capturing its own rook, as
+    /// in Chess960, since the king's move could also be an ordinary one.
+    pub fn turn_to_uci(&self, turn: &Turn) -> String {
+        let to = match turn.additional_move {
+            Some((rook_from, _)) if turn.from.col() != 4 || !matches!(rook_from.col(), 0 | 7) => {
+                rook_from
+            }
+            _ => turn.to,
+        };
+        let mut uci = format!("{}{}", turn.from, to).to_ascii_lowercase();
+        if let Some(promo) = turn.promote_to {
+            uci.push(promo.letter().to_ascii_lowercase());
+        }
+        uci
+    }
+
+    /// Find the legal turn described by a move in UCI long algebraic
+    /// notation, such as `e2e4` or `e7e8q`.
+    ///
+    /// Castling may be given either as the king's move (`e1g1`) or, as in
+    /// Chess960, as the king capturing its own rook (`e1h1`). Returns `None`
+    /// if the move is malformed or illegal.
+    pub fn parse_uci(&mut self, uci: &str) -> Option<Turn> {
+        if !(4..=5).contains(&uci.len()) || !uci.is_ascii() {
+            return None;
+        }
+        let from = uci[0..2].parse().ok()?;
+        let to = uci[2..4].parse().ok()?;
+        let promote_to = match uci[4..].chars().next() {
+            Some(c) => Some(
+                PROMOTABLE_TYPES
+                    .into_iter()
+                    .find(|p| p.letter() == c.to_ascii_uppercase())?,
+            ),
+            None => None,
+        };
+
+        self.find_move(from, to, promote_to)
+    }
+}
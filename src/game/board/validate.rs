@@ -0,0 +1,181 @@
+use alloc::{vec, vec::Vec};
+use core::fmt::Display;
+
+use crate::game::{CastlingSide, Color, PieceType, Position};
+
+use super::Board;
+
+/// A reason why a position couldn't occur in a legal game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PositionProblem {
+    /// The given color has no king
+    MissingKing(Color),
+
+    /// The given color has more than one king
+    TooManyKings(Color),
+
+    /// The two kings are on neighbouring squares
+    KingsAdjacent,
+
+    /// The player who just moved has been left in check
+    /// Includes the color of that player
+    OpponentInCheck(Color),
+
+    /// The given color has more than 8 pawns
+    TooManyPawns(Color),
+
+    /// There is a pawn on the first or last rank
+    PawnOnBackRank(Position),
+
+    /// The castling rights allow castling on the given side, but the king
+    /// and rook aren't where they would need to be
+    InvalidCastling(Color, CastlingSide),
+
+    /// The en passant target isn't just behind a pawn that could have moved
+    /// two squares on the previous turn
+    InvalidEnPassant(Position),
+}
+
+impl Display for PositionProblem {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PositionProblem::MissingKing(color) => write!(f, "{} has no king", color),
+            PositionProblem::TooManyKings(color) => write!(f, "{} has more than one king", color),
+            PositionProblem::KingsAdjacent => write!(f, "the kings are next to each other"),
+            PositionProblem::OpponentInCheck(color) => {
+                write!(f, "{} is in check, but it isn't their turn", color)
+            }
+            PositionProblem::TooManyPawns(color) => {
+                write!(f, "{} has more than 8 pawns", color)
+            }
+            PositionProblem::PawnOnBackRank(pos) => write!(f, "there is a pawn on {}", pos),
+            PositionProblem::InvalidCastling(color, side) => write!(
+                f,
+                "{} can castle {}, but the king and rook aren't in place",
+                color,
+                match side {
+                    CastlingSide::KingSide => "king side",
+                    CastlingSide::QueenSide => "queen side",
+                }
+            ),
+            PositionProblem::InvalidEnPassant(pos) => {
+                write!(f, "{} can't be an en passant target", pos)
+            }
+        }
+    }
+}
+
+impl Board {
+    /// Check whether the position could occur in a legal game, returning a
+    /// list of any problems found.
+    ///
+    /// This is useful for checking positions from board editors or FEN
+    /// strings, which may not follow the rules. An empty list means no
+    /// problems were found, although some impossible positions may not be
+    /// detected.
+    pub fn validate_position(&self) -> Vec<PositionProblem> {
+        let mut problems = vec![];
+
+        let mut kings = [vec![], vec![]];
+        let mut pawns = [0, 0];
+        for pos in self.occupied() {
+            let piece = self.at_position(pos).unwrap();
+            let c = match piece.color {
+                Color::White => 0,
+                Color::Black => 1,
+            };
+            match piece.kind {
+                PieceType::King => kings[c].push(pos),
+                PieceType::Pawn => {
+                    pawns[c] += 1;
+                    if pos.row() == 0 || pos.row() == 7 {
+                        problems.push(PositionProblem::PawnOnBackRank(pos));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (color, c) in [(Color::White, 0), (Color::Black, 1)] {
+            match kings[c].len() {
+                0 => problems.push(PositionProblem::MissingKing(color)),
+                1 => {}
+                _ => problems.push(PositionProblem::TooManyKings(color)),
+            }
+            if pawns[c] > 8 {
+                problems.push(PositionProblem::TooManyPawns(color));
+            }
+        }
+
+        // Checks involving the kings only make sense if there is exactly one
+        // of each
+        if kings[0].len() == 1 && kings[1].len() == 1 {
+            let (white, black) = (kings[0][0], kings[1][0]);
+            if (white.row() - black.row()).abs() <= 1 && (white.col() - black.col()).abs() <= 1 {
+                problems.push(PositionProblem::KingsAdjacent);
+            }
+            if self.is_king_attacked(!self.whose_turn) {
+                problems.push(PositionProblem::OpponentInCheck(!self.whose_turn));
+            }
+        }
+
+        let rights = self.castling_rights();
+        for color in [Color::White, Color::Black] {
+            for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+                if let Some(col) = rights.rook_col(color, side) {
+                    if !self.is_castling_valid(color, side, col) {
+                        problems.push(PositionProblem::InvalidCastling(color, side));
+                    }
+                }
+            }
+        }
+
+        if let Some(target) = self.en_passant_target {
+            if !self.is_en_passant_valid(target) {
+                problems.push(PositionProblem::InvalidEnPassant(target));
+            }
+        }
+
+        problems
+    }
+
+    /// Returns whether the king and a rook in the given column are in place
+    /// to castle on the given side
+    pub(super) fn is_castling_valid(&self, color: Color, side: CastlingSide, rook_col: i8) -> bool {
+        let row = color.get_home();
+        let is_piece = |col: i8, kind: PieceType| {
+            matches!(
+                self.at_position(Position::new(row, col)),
+                Some(piece) if piece.kind == kind && piece.color == color
+            )
+        };
+        if !is_piece(rook_col, PieceType::Rook) {
+            return false;
+        }
+        // The king must be on the home row, with the rook on the correct
+        // side of it
+        (0..8).any(|col| {
+            is_piece(col, PieceType::King) && (rook_col - col) * side.get_direction() > 0
+        })
+    }
+
+    /// Returns whether the given en passant target could have been left by
+    /// the other player's previous turn
+    pub(super) fn is_en_passant_valid(&self, target: Position) -> bool {
+        let direction = self.whose_turn.get_direction();
+        // The target must be on the third rank from the other player's side
+        if target.row() != (!self.whose_turn).get_home() - direction * 2 {
+            return false;
+        }
+        // With the pawn that moved just in front of it, and the squares it
+        // moved through empty
+        let pawn = target.offset(-direction, 0);
+        let origin = target.offset(direction, 0);
+        self.at_position(target).is_none()
+            && origin.is_some_and(|pos| self.at_position(pos).is_none())
+            && matches!(
+                pawn.and_then(|pos| self.at_position(pos)),
+                Some(piece) if piece.kind == PieceType::Pawn && piece.color != self.whose_turn
+            )
+    }
+}
@@ -0,0 +1,114 @@
+use super::{Color, Position};
+
+/// Side of the board that a king castles towards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastlingSide {
+    /// Towards the H file (O-O)
+    KingSide,
+    /// Towards the A file (O-O-O)
+    QueenSide,
+}
+
+impl CastlingSide {
+    /// Returns a column offset pointing from the king towards this side
+    pub fn get_direction(self) -> i8 {
+        match self {
+            CastlingSide::KingSide => 1,
+            CastlingSide::QueenSide => -1,
+        }
+    }
+
+    /// Returns the column the king ends up on after castling
+    pub fn king_destination(self) -> i8 {
+        match self {
+            CastlingSide::KingSide => 6,
+            CastlingSide::QueenSide => 2,
+        }
+    }
+
+    /// Returns the column the rook ends up on after castling
+    pub fn rook_destination(self) -> i8 {
+        match self {
+            CastlingSide::KingSide => 5,
+            CastlingSide::QueenSide => 3,
+        }
+    }
+}
+
+/// Which rooks are still able to castle.
+///
+/// The column of each rook is stored rather than a flag, so that Chess960
+/// positions, where the rooks don't start in the corners, are supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CastlingRights {
+    /// Rook columns, indexed by color then side
+    rooks: [[Option<i8>; 2]; 2],
+}
+
+impl CastlingRights {
+    /// Castling rights for the standard starting position
+    pub fn from_start() -> Self {
+        Self {
+            rooks: [[Some(7), Some(0)]; 2],
+        }
+    }
+
+    fn index(color: Color, side: CastlingSide) -> (usize, usize) {
+        let c = match color {
+            Color::White => 0,
+            Color::Black => 1,
+        };
+        let s = match side {
+            CastlingSide::KingSide => 0,
+            CastlingSide::QueenSide => 1,
+        };
+        (c, s)
+    }
+
+    /// Returns whether the given color may still castle on the given side
+    pub fn can_castle(&self, color: Color, side: CastlingSide) -> bool {
+        self.rook_col(color, side).is_some()
+    }
+
+    /// Returns whether the given color may still castle on either side
+    pub fn can_castle_any(&self, color: Color) -> bool {
+        self.can_castle(color, CastlingSide::KingSide)
+            || self.can_castle(color, CastlingSide::QueenSide)
+    }
+
+    /// Returns the column (0-7) of the rook that can castle on the given
+    /// side, if any
+    pub fn rook_col(&self, color: Color, side: CastlingSide) -> Option<i8> {
+        let (c, s) = Self::index(color, side);
+        self.rooks[c][s]
+    }
+
+    /// Allow castling with the rook in the given column, or disallow
+    /// castling on that side by passing `None`
+    pub fn set(&mut self, color: Color, side: CastlingSide, rook_col: Option<i8>) {
+        if let Some(col) = rook_col {
+            assert!((0..8).contains(&col));
+        }
+        let (c, s) = Self::index(color, side);
+        self.rooks[c][s] = rook_col;
+    }
+
+    /// Remove the castling right for the rook of the given color at the given
+    /// position, if there is one, such as when it moves or is captured
+    pub fn remove_rook(&mut self, color: Color, pos: Position) {
+        if pos.row() != color.get_home() {
+            return;
+        }
+        for side in [CastlingSide::KingSide, CastlingSide::QueenSide] {
+            if self.rook_col(color, side) == Some(pos.col()) {
+                self.set(color, side, None);
+            }
+        }
+    }
+
+    /// Remove all castling rights for the given color
+    pub fn remove(&mut self, color: Color) {
+        self.set(color, CastlingSide::KingSide, None);
+        self.set(color, CastlingSide::QueenSide, None);
+    }
+}
@@ -0,0 +1,154 @@
+use alloc::string::ToString;
+use core::fmt::Display;
+
+use super::{Board, CastlingSide, PieceType, Position, Turn};
+
+// Flags, stored in the top four bits
+const QUIET: u16 = 0;
+const DOUBLE_PUSH: u16 = 1;
+const KING_CASTLE: u16 = 2;
+const QUEEN_CASTLE: u16 = 3;
+const CAPTURE: u16 = 4;
+const EN_PASSANT: u16 = 5;
+/// Set for promotions, with the kind of piece in the lowest two bits
+const PROMOTION: u16 = 8;
+
+/// Promotion kinds, in the order they are encoded
+const PROMOTIONS: [PieceType; 4] = [
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+];
+
+/// A move packed into 16 bits, which is cheap to copy and store, such as in
+/// transposition tables and move ordering heuristics.
+///
+/// Unlike `Turn`, this doesn't record the kind of piece being moved, so a
+/// board is needed to convert it back. Castling is stored as the king moving
+/// onto its own rook, so that Chess960 castling can be described.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move(u16);
+
+impl Move {
+    fn new(from: Position, to: Position, flags: u16) -> Self {
+        Move(from.pos() as u16 | (to.pos() as u16) << 6 | flags << 12)
+    }
+
+    fn flags(self) -> u16 {
+        self.0 >> 12
+    }
+
+    /// Position of the piece being moved
+    pub fn from_square(self) -> Position {
+        Position::from((self.0 & 0x3f) as i8)
+    }
+
+    /// Position the piece is moving to, or the position of the rook when
+    /// castling
+    pub fn to_square(self) -> Position {
+        Position::from((self.0 >> 6 & 0x3f) as i8)
+    }
+
+    /// The kind of piece being promoted to, if any
+    pub fn promotion(self) -> Option<PieceType> {
+        if self.flags() & PROMOTION != 0 {
+            Some(PROMOTIONS[(self.flags() & 3) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether a piece is captured, including en passant
+    pub fn is_capture(self) -> bool {
+        self.flags() & CAPTURE != 0
+    }
+
+    pub fn is_en_passant(self) -> bool {
+        self.flags() == EN_PASSANT
+    }
+
+    pub fn is_castling(self) -> bool {
+        matches!(self.flags(), KING_CASTLE | QUEEN_CASTLE)
+    }
+
+    /// Returns whether this is a pawn moving two squares
+    pub fn is_double_push(self) -> bool {
+        self.flags() == DOUBLE_PUSH
+    }
+
+    /// Convert the move back into a turn, using the board it was made from to
+    /// find the kind of piece being moved
+    pub fn to_turn(self, board: &Board) -> Turn {
+        let (from, to) = (self.from_square(), self.to_square());
+        let kind = board
+            .at_position(from)
+            .expect("Move non-existent piece")
+            .kind;
+        let side = match self.flags() {
+            KING_CASTLE => Some(CastlingSide::KingSide),
+            QUEEN_CASTLE => Some(CastlingSide::QueenSide),
+            _ => None,
+        };
+        if let Some(side) = side {
+            let row = from.row();
+            Turn::new_additional(
+                kind,
+                (from, Position::new(row, side.king_destination())),
+                (to, Position::new(row, side.rook_destination())),
+            )
+        } else if let Some(promo) = self.promotion() {
+            Turn::new_promotion(kind, from, to, promo, self.is_capture())
+        } else if self.is_en_passant() {
+            Turn::new_capture_complex(kind, from, to, Position::new(from.row(), to.col()))
+        } else if self.is_capture() {
+            Turn::new_capture(kind, from, to)
+        } else {
+            Turn::new_basic(kind, from, to)
+        }
+    }
+}
+
+impl From<&Turn> for Move {
+    fn from(turn: &Turn) -> Self {
+        if let Some((rook_from, _)) = turn.additional_move {
+            let flags = if rook_from.col() > turn.from.col() {
+                KING_CASTLE
+            } else {
+                QUEEN_CASTLE
+            };
+            return Move::new(turn.from, rook_from, flags);
+        }
+        let capture = if turn.capture.is_some() { CAPTURE } else { 0 };
+        let flags = if let Some(promo) = turn.promote_to {
+            let index = PROMOTIONS
+                .iter()
+                .position(|&p| p == promo)
+                .expect("Invalid promotion");
+            PROMOTION | capture | index as u16
+        } else if turn.capture.is_some_and(|pos| pos != turn.to) {
+            EN_PASSANT
+        } else if turn.kind == PieceType::Pawn && (turn.to.row() - turn.from.row()).abs() == 2 {
+            DOUBLE_PUSH
+        } else {
+            capture | QUIET
+        };
+        Move::new(turn.from, turn.to, flags)
+    }
+}
+
+impl Display for Move {
+    /// Write the move in long algebraic notation, such as `e2e4` or `e7e8q`
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            self.from_square().to_string().to_ascii_lowercase(),
+            self.to_square().to_string().to_ascii_lowercase()
+        )?;
+        if let Some(promo) = self.promotion() {
+            write!(f, "{}", promo.letter().to_ascii_lowercase())?;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
-use std::{ops::Not, fmt::Display};
+use alloc::string::ToString;
+use core::{fmt::Display, ops::Not};
 
 use super::board::FenError;
 
@@ -19,6 +20,14 @@ impl Color {
         }
     }
 
+    /// Returns a unique index from 0..2 for this color, for use in tables
+    pub fn index(self) -> usize {
+        match self {
+            Color::White => 0,
+            Color::Black => 1,
+        }
+    }
+
     /// Returns the index of the row that is home for this color
     pub fn get_home(self) -> i8 {
         match self {
@@ -48,11 +57,15 @@ impl Not for Color {
 }
 
 impl Display for Color {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", match self {
-            Color::White => "White",
-            Color::Black => "Black",
-        })?;
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Color::White => "White",
+                Color::Black => "Black",
+            }
+        )?;
         Ok(())
     }
 }
@@ -6,21 +6,41 @@ pub enum DrawReason {
     /// Same position 3 times
     ThreefoldRepetition,
 
+    /// Same position 5 times
+    /// Unlike threefold repetition, this doesn't need to be claimed
+    FivefoldRepetition,
+
     /// 50 moves without a capture or pawn push
     FiftyMoveRule,
 
+    /// 75 moves without a capture or pawn push
+    /// Unlike the 50 move rule, this doesn't need to be claimed
+    SeventyFiveMoveRule,
+
+    /// Same position as many times as the `Rules` require, when that isn't
+    /// 3 times
+    /// Includes the number of times
+    RepetitionLimit(usize),
+
+    /// As many moves without a capture or pawn push as the `Rules` require,
+    /// when that isn't 50 moves
+    /// Includes the number of moves
+    MoveLimit(u32),
+
     /// No moves available, but not checkmate
     Stalemate,
 
     /// Not enough material for checkmate
     InsufficientMaterial,
 
+    /// No sequence of legal moves can lead to checkmate, despite there
+    /// being enough material, such as when the pawns are locked
+    DeadPosition,
+
     /// Both players agreed to it
-    /// Not tracked
     MutualAgreement,
 
     /// Time out, with remaining player having insufficient mating material
-    /// Not tracked
     TimeOut,
 }
 
@@ -31,11 +51,9 @@ pub enum WinReason {
     Checkmate,
 
     /// Opponent timed out
-    /// Not tracked
     TimeOut,
 
     /// Opponent resigned
-    /// Not tracked
     Resigned,
 }
 
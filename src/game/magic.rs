@@ -0,0 +1,264 @@
+//! Magic bitboard lookup tables for sliding piece attacks.
+//!
+//! For each square, the squares that could block a slider are masked out of
+//! the occupancy, then multiplied by a "magic" number that maps every
+//! possible set of blockers to a unique index into a table of precomputed
+//! attacks. The magic numbers were found by trial and error, and the tables
+//! are built the first time they are used.
+//!
+//! Building the tables lazily needs the `std` feature, so without it,
+//! `bitboard` finds attacks by walking along each direction instead.
+
+use alloc::{vec, vec::Vec};
+use std::sync::OnceLock;
+
+use super::{
+    bitboard::{ray_attacks, BISHOP_DIRECTIONS, ROOK_DIRECTIONS},
+    Bitboard, Position,
+};
+
+const ROOK_MAGICS: [u64; 64] = [
+    0x2080_0020_8040_0010,
+    0x00c0_0020_0140_1000,
+    0x2100_1100_0840_2002,
+    0x0880_0800_8104_1000,
+    0x0200_0200_2004_1008,
+    0x2300_0400_0801_0012,
+    0x0c00_2830_0400_8201,
+    0x0180_0100_0040_7a80,
+    0x0168_8000_8040_0020,
+    0x0010_4000_4020_1000,
+    0x1001_0020_0100_1048,
+    0x1001_0024_0810_0100,
+    0x0801_0004_0801_0012,
+    0x4001_0002_0900_0400,
+    0x08a2_0004_c802_0001,
+    0x2002_8011_4500_2280,
+    0x0080_8600_2100_4200,
+    0x0010_00c0_0940_2002,
+    0x00b0_0020_0400_2800,
+    0x100a_8080_1002_0800,
+    0x9400_8080_0400_0800,
+    0x0090_8080_0400_0200,
+    0x0000_0400_1081_0208,
+    0x2000_0200_0044_8534,
+    0x4104_4004_8000_8033,
+    0x0000_8101_0020_4000,
+    0x0440_4309_0020_0010,
+    0x4600_2409_0010_0100,
+    0x0804_0801_0011_0004,
+    0x0001_0003_0008_0400,
+    0x0004_0844_0001_1002,
+    0x0023_0402_0000_8041,
+    0x0580_0500_4300_2080,
+    0x0400_8040_0280_2008,
+    0x0001_0020_0100_4010,
+    0x0080_200a_0200_1040,
+    0x600d_4802_8080_2400,
+    0x400b_8002_0180_0c00,
+    0x2408_2110_0400_4208,
+    0x0200_2110_8200_0844,
+    0x0020_8040_1020_8000,
+    0x5030_0040_2010_4000,
+    0xa042_0840_8022_0010,
+    0x4088_0800_1000_8080,
+    0x5002_0801_0011_0004,
+    0x2012_0020_1004_0400,
+    0x0040_3182_1044_0008,
+    0x0120_9410_4082_0001,
+    0x1000_8001_0040_2100,
+    0x0040_0020_1000_4840,
+    0x8108_4500_2000_1900,
+    0x0200_2040_0812_0200,
+    0x0080_800c_0018_0180,
+    0x0885_0004_0042_0900,
+    0x2308_0201_1008_c400,
+    0x3801_7408_9143_2200,
+    0x0a00_2502_1202_4082,
+    0x0000_8820_4000_1105,
+    0x0042_1020_8200_0a42,
+    0xc401_2108_1000_0501,
+    0x0241_0010_0248_0005,
+    0x0081_0004_0088_0241,
+    0x0000_0090_0802_4124,
+    0x0048_1229_8041_0402,
+];
+
+const BISHOP_MAGICS: [u64; 64] = [
+    0x1862_2210_0622_0044,
+    0x2104_a142_0202_0060,
+    0x2804_0812_2044_4001,
+    0x2102_4089_0001_0001,
+    0x0002_0210_0004_0002,
+    0x08c3_1008_0500_4300,
+    0x1084_0401_2492_0050,
+    0x8900_4400_4338_2010,
+    0x2401_4108_0214_0040,
+    0x0901_2004_5420_8020,
+    0x0000_0902_1602_0541,
+    0x1283_8440_4080_0804,
+    0x0521_8404_2000_0803,
+    0x0800_0104_0240_0c40,
+    0x0000_408e_1010_0404,
+    0x0009_8100_4842_0800,
+    0x2004_2110_0428_6808,
+    0x1308_0a10_0138_0080,
+    0x0008_8010_0422_0020,
+    0x0024_0008_0248_0800,
+    0x1461_0011_9040_0401,
+    0x0020_4002_0050_0440,
+    0x0003_0004_0901_9000,
+    0x000c_2082_0d01_1802,
+    0x0008_0400_2164_100c,
+    0x0004_8400_a001_1404,
+    0x5018_1103_0804_4100,
+    0x0048_a008_0401_0020,
+    0x0007_8400_0080_2000,
+    0x8808_a200_7500_4220,
+    0x8014_0400_0082_2100,
+    0x110c_0300_0e25_1101,
+    0x0081_0948_2020_2010,
+    0x0008_0410_0004_4100,
+    0x00c1_2028_0894_0800,
+    0x8108_1008_2104_0400,
+    0x1240_0100_1001_0041,
+    0x0810_0040_8001_1000,
+    0x00a2_0c04_0180_4a00,
+    0x4001_4c00_2005_0500,
+    0x5805_0820_1204_2480,
+    0x2004_0221_4403_1000,
+    0x2082_0020_2420_4808,
+    0x0800_0042_0080_0800,
+    0x0410_0202_0410_0a02,
+    0x80c1_2040_8080_4101,
+    0x0010_104e_0180_0042,
+    0x0008_0081_0c40_0208,
+    0x1000_80b0_0820_1210,
+    0x8000_4406_0511_2101,
+    0x0002_0200_8c44_0040,
+    0x9004_0022_1044_2200,
+    0x2032_0140_8822_2045,
+    0x0c00_2022_22c2_0000,
+    0x0140_0408_20a5_0100,
+    0x0222_104c_2902_4018,
+    0x0200_1101_2120_2004,
+    0x0800_1042_00b0_0802,
+    0x0000_4014_2402_0801,
+    0x4000_0000_0420_8840,
+    0x0802_e000_4010_4100,
+    0x0300_0020_a042_4080,
+    0x0011_c004_0818_8121,
+    0x0848_0208_2204_0013,
+];
+
+/// Lookup information for a single square
+#[derive(Debug, Clone, Copy, Default)]
+struct Magic {
+    /// Squares whose occupancy affects the attacks
+    mask: u64,
+    magic: u64,
+    shift: u32,
+    /// Start of this square's attacks in the shared table
+    offset: usize,
+}
+
+impl Magic {
+    fn index(&self, occupied: Bitboard) -> usize {
+        self.offset + ((occupied.0 & self.mask).wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+struct MagicTables {
+    rook: [Magic; 64],
+    bishop: [Magic; 64],
+    attacks: Vec<Bitboard>,
+}
+
+static TABLES: OnceLock<MagicTables> = OnceLock::new();
+
+fn tables() -> &'static MagicTables {
+    TABLES.get_or_init(|| {
+        let mut attacks = vec![];
+        let mut rook = [Magic::default(); 64];
+        let mut bishop = [Magic::default(); 64];
+        for i in 0..64 {
+            let pos = Position::from(i as i8);
+            rook[i] = build_magic(pos, ROOK_DIRECTIONS, ROOK_MAGICS[i], &mut attacks);
+            bishop[i] = build_magic(pos, BISHOP_DIRECTIONS, BISHOP_MAGICS[i], &mut attacks);
+        }
+        MagicTables {
+            rook,
+            bishop,
+            attacks,
+        }
+    })
+}
+
+/// Returns the squares attacked by a rook on the given square, where
+/// `occupied` contains the pieces that block it
+pub fn rook_attacks(pos: Position, occupied: Bitboard) -> Bitboard {
+    let tables = tables();
+    tables.attacks[tables.rook[pos.pos()].index(occupied)]
+}
+
+/// Returns the squares attacked by a bishop on the given square, where
+/// `occupied` contains the pieces that block it
+pub fn bishop_attacks(pos: Position, occupied: Bitboard) -> Bitboard {
+    let tables = tables();
+    tables.attacks[tables.bishop[pos.pos()].index(occupied)]
+}
+
+/// Returns the squares whose occupancy affects the attacks of a slider on
+/// the given square, which excludes the last square along each direction,
+/// as it is attacked whether or not there is a piece on it
+fn relevant_mask(pos: Position, directions: [(i8, i8); 4]) -> u64 {
+    let mut mask = 0;
+    for (r, c) in directions {
+        let mut square = pos;
+        while let Some(next) = square.offset(r, c) {
+            if next.offset(r, c).is_none() {
+                break;
+            }
+            mask |= 1 << next.pos();
+            square = next;
+        }
+    }
+    mask
+}
+
+/// Build the lookup for the given square using its magic number, adding its
+/// attacks to the end of `attacks`
+fn build_magic(
+    pos: Position,
+    directions: [(i8, i8); 4],
+    magic: u64,
+    attacks: &mut Vec<Bitboard>,
+) -> Magic {
+    let mask = relevant_mask(pos, directions);
+    let bits = mask.count_ones();
+    let magic = Magic {
+        mask,
+        magic,
+        shift: 64 - bits,
+        offset: attacks.len(),
+    };
+    attacks.resize(attacks.len() + (1 << bits), Bitboard::EMPTY);
+
+    // Fill in the attacks for every subset of the mask
+    let mut subset: u64 = 0;
+    loop {
+        let attack = ray_attacks(pos, Bitboard(subset), directions);
+        let slot = &mut attacks[magic.index(Bitboard(subset))];
+        debug_assert!(
+            slot.is_empty() || *slot == attack,
+            "Magic number for {} collides",
+            pos
+        );
+        *slot = attack;
+        subset = subset.wrapping_sub(mask) & mask;
+        if subset == 0 {
+            break;
+        }
+    }
+    magic
+}
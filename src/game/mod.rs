@@ -1,13 +1,54 @@
+//! Chess rules, positions and notation.
+//!
+//! This module exposes the full API, including lower-level items whose
+//! details may change as the board representation is reworked. Prefer the
+//! items re-exported from `prelude`, which are kept stable.
+
+mod bitboard;
 mod board;
+mod castling;
+mod chess_move;
 mod color;
 mod game_state;
+#[cfg(feature = "std")]
+mod magic;
+mod move_list;
+mod perft_suite;
+mod pgn;
+#[cfg(feature = "std")]
+mod pgn_check;
 mod piece;
 mod position;
+mod rules;
+mod tags;
+mod tt;
 mod turn;
+mod zobrist;
 
-pub use board::Board;
+pub use bitboard::{
+    bishop_attacks, king_attacks, knight_attacks, pawn_attacks, rook_attacks, Bitboard,
+};
+pub use board::{
+    check_moves, parse_position, perft, Board, BoardBuilder, BoardState, ClaimError, FenError,
+    IllegalMoveReason, MoveCheck, MoveError, MoveGen, PerftCache, PositionBatch,
+    PositionInputError, PositionProblem, SanError, Stats, BATCH_PLANES,
+};
+pub use castling::{CastlingRights, CastlingSide};
+pub use chess_move::Move;
 pub use color::Color;
 pub use game_state::{DrawReason, GameState, WinReason};
-pub use piece::PieceType;
-pub use position::Position;
+pub use move_list::{MoveList, MAX_MOVES};
+pub use perft_suite::{
+    parse_perft_suite, verify_perft_suite, PerftMismatch, PerftRecord, PerftSuiteError,
+};
+#[cfg(feature = "std")]
+pub use pgn::PgnReader;
+pub use pgn::{PgnError, PgnGame};
+#[cfg(feature = "std")]
+pub use pgn_check::{PgnCheck, PgnProblem, PgnValidator};
+pub use piece::{PieceType, PIECE_TYPES};
+pub use position::{ParsePositionError, Position};
+pub use rules::{Rules, RulesError};
+pub use tags::{GameTags, ParsePgnDateError, PgnDate, PgnResult};
+pub use tt::{score_from_tt, score_to_tt, Bound, TranspositionTable, TtEntry};
 pub use turn::Turn;
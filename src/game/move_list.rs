@@ -0,0 +1,142 @@
+use alloc::vec::Vec;
+use core::ops::Index;
+
+use super::Turn;
+
+/// The most turns that a `MoveList` can hold, which is more than the number
+/// of legal moves in any reachable position
+pub const MAX_MOVES: usize = 256;
+
+/// A list of turns stored inline rather than on the heap, so that move
+/// generation doesn't need to allocate.
+#[derive(Debug, Clone)]
+pub struct MoveList {
+    turns: [Option<Turn>; MAX_MOVES],
+    len: usize,
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MoveList {
+    /// Create an empty list
+    pub fn new() -> Self {
+        Self {
+            turns: [const { None }; MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    /// Add a turn to the end of the list.
+    ///
+    /// Panics if the list already holds `MAX_MOVES` turns.
+    pub fn push(&mut self, turn: Turn) {
+        assert!(self.len < MAX_MOVES, "Move list is full");
+        self.turns[self.len] = Some(turn);
+        self.len += 1;
+    }
+
+    /// Remove and return the last turn in the list
+    pub fn pop(&mut self) -> Option<Turn> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        self.turns[self.len].take()
+    }
+
+    /// Remove every turn from the list
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the turn at the given index, if there is one
+    pub fn get(&self, index: usize) -> Option<&Turn> {
+        self.turns[..self.len].get(index)?.as_ref()
+    }
+
+    /// Swap the turns at the given indexes, such as when ordering moves
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.turns[..self.len].swap(a, b);
+    }
+
+    /// Keep only the turns for which the given function returns `true`,
+    /// without changing their order
+    pub fn retain(&mut self, mut keep: impl FnMut(&Turn) -> bool) {
+        let mut kept = 0;
+        for i in 0..self.len {
+            if self.turns[i].as_ref().is_some_and(&mut keep) {
+                self.turns.swap(kept, i);
+                kept += 1;
+            }
+        }
+        // Anything after the kept turns was removed
+        for turn in &mut self.turns[kept..self.len] {
+            *turn = None;
+        }
+        self.len = kept;
+    }
+
+    /// Returns whether the list contains the given turn
+    pub fn contains(&self, turn: &Turn) -> bool {
+        self.iter().any(|t| t == turn)
+    }
+
+    /// Iterate over the turns in the list
+    pub fn iter(&self) -> impl Iterator<Item = &Turn> {
+        self.turns[..self.len].iter().flatten()
+    }
+}
+
+impl Index<usize> for MoveList {
+    type Output = Turn;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Move list index out of range")
+    }
+}
+
+impl IntoIterator for MoveList {
+    type Item = Turn;
+    type IntoIter = core::iter::Flatten<core::array::IntoIter<Option<Turn>, MAX_MOVES>>;
+
+    /// Iterate over the turns in the list, taking ownership of them. Unused
+    /// slots are always `None`, so are skipped.
+    fn into_iter(self) -> Self::IntoIter {
+        self.turns.into_iter().flatten()
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Turn;
+    type IntoIter = core::iter::Flatten<core::slice::Iter<'a, Option<Turn>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.turns[..self.len].iter().flatten()
+    }
+}
+
+impl Extend<Turn> for MoveList {
+    fn extend<T: IntoIterator<Item = Turn>>(&mut self, iter: T) {
+        for turn in iter {
+            self.push(turn);
+        }
+    }
+}
+
+impl From<MoveList> for Vec<Turn> {
+    fn from(list: MoveList) -> Self {
+        list.into_iter().collect()
+    }
+}
@@ -0,0 +1,158 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{error::Error, fmt::Display};
+
+use super::{Board, FenError};
+
+/// Error when reading a perft suite
+/// Each variant includes the line number of the offending record
+#[derive(Debug)]
+pub enum PerftSuiteError {
+    /// The position of a record couldn't be parsed
+    InvalidFen(usize, FenError),
+
+    /// A depth operation wasn't of the form `D<depth> <count>`
+    /// Includes the offending operation
+    InvalidDepth(usize, String),
+}
+
+impl Display for PerftSuiteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PerftSuiteError::InvalidFen(line, e) => write!(f, "line {}: invalid FEN: {}", line, e),
+            PerftSuiteError::InvalidDepth(line, op) => {
+                write!(f, "line {}: invalid depth '{}'", line, op)
+            }
+        }
+    }
+}
+
+impl Error for PerftSuiteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PerftSuiteError::InvalidFen(_, e) => Some(e),
+            PerftSuiteError::InvalidDepth(..) => None,
+        }
+    }
+}
+
+/// A position from a perft suite, along with its known counts
+#[derive(Debug, Clone)]
+pub struct PerftRecord {
+    /// Line number of the record in the suite, starting from 1
+    pub line: usize,
+
+    /// The position, as it was given
+    pub fen: String,
+
+    /// Known number of positions reachable at each depth
+    pub counts: Vec<(u32, u64)>,
+
+    board: Board,
+}
+
+/// A count from a perft suite that move generation didn't match
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerftMismatch {
+    /// Line number of the record in the suite, starting from 1
+    pub line: usize,
+    pub fen: String,
+    pub depth: u32,
+    pub expected: u64,
+    pub found: u64,
+}
+
+impl Display for PerftMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "line {}: expected {} positions at depth {} but found {} ({})",
+            self.line, self.expected, self.depth, self.found, self.fen
+        )
+    }
+}
+
+impl Error for PerftMismatch {}
+
+impl PerftRecord {
+    /// Parse a record of the form `<fen> ;D1 <count> ;D2 <count> ...`, as
+    /// used by the standard perft suites.
+    ///
+    /// The FEN may leave out the move counters. Other operations are
+    /// ignored. Returns `None` for blank lines and `#` comments.
+    pub fn parse(line: usize, record: &str) -> Result<Option<Self>, PerftSuiteError> {
+        let record = record.trim();
+        if record.is_empty() || record.starts_with('#') {
+            return Ok(None);
+        }
+        let mut operations = record.split(';');
+        let fen = operations.next().unwrap_or_default().trim();
+        let board = if fen.split_ascii_whitespace().count() == 4 {
+            Board::from_epd(fen)
+        } else {
+            Board::from_fen(fen)
+        }
+        .map_err(|e| PerftSuiteError::InvalidFen(line, e))?;
+
+        let mut counts = Vec::new();
+        for operation in operations.map(str::trim) {
+            let Some(rest) = operation
+                .strip_prefix('D')
+                .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+            else {
+                continue;
+            };
+            let invalid = || PerftSuiteError::InvalidDepth(line, operation.to_string());
+            let (depth, count) = rest.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            counts.push((
+                depth.parse().map_err(|_| invalid())?,
+                count.trim().parse().map_err(|_| invalid())?,
+            ));
+        }
+        Ok(Some(Self {
+            line,
+            fen: fen.to_string(),
+            counts,
+            board,
+        }))
+    }
+
+    /// Check move generation against the known counts, up to the given
+    /// depth, returning the first count that doesn't match
+    pub fn verify(&self, max_depth: u32) -> Result<(), PerftMismatch> {
+        let mut board = self.board.clone();
+        for &(depth, expected) in self.counts.iter().filter(|(d, _)| *d <= max_depth) {
+            let found = board.perft(depth);
+            if found != expected {
+                return Err(PerftMismatch {
+                    line: self.line,
+                    fen: self.fen.clone(),
+                    depth,
+                    expected,
+                    found,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse every record of a perft suite, such as `perftsuite.epd`, with one
+/// record per line
+pub fn parse_perft_suite(suite: &str) -> Result<Vec<PerftRecord>, PerftSuiteError> {
+    suite
+        .lines()
+        .enumerate()
+        .filter_map(|(i, record)| PerftRecord::parse(i + 1, record).transpose())
+        .collect()
+}
+
+/// Check move generation against every record of a perft suite, up to the
+/// given depth, stopping at the first count that doesn't match
+pub fn verify_perft_suite(records: &[PerftRecord], max_depth: u32) -> Result<(), PerftMismatch> {
+    records
+        .iter()
+        .try_for_each(|record| record.verify(max_depth))
+}
@@ -0,0 +1,330 @@
+use alloc::string::{String, ToString};
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::{error::Error, fmt::Display};
+#[cfg(feature = "std")]
+use std::io::{self, BufRead};
+
+use super::{Board, FenError, GameTags, PgnResult};
+
+/// Error when reading a game from a PGN file
+#[derive(Debug)]
+pub enum PgnError {
+    /// Failed to read from the underlying reader
+    #[cfg(feature = "std")]
+    Io(io::Error),
+
+    /// A tag pair wasn't of the form `[Name "Value"]`
+    /// Includes the line containing the tag
+    InvalidTag(String),
+
+    /// The FEN tag couldn't be parsed
+    InvalidFen(FenError),
+
+    /// A move in the movetext couldn't be played
+    /// Includes the offending move
+    IllegalMove(String),
+}
+
+impl Display for PgnError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            PgnError::Io(e) => write!(f, "failed to read PGN: {}", e),
+            PgnError::InvalidTag(line) => write!(f, "invalid tag pair '{}'", line),
+            PgnError::InvalidFen(e) => write!(f, "invalid FEN tag: {}", e),
+            PgnError::IllegalMove(san) => write!(f, "illegal move '{}'", san),
+        }
+    }
+}
+
+impl Error for PgnError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            PgnError::Io(e) => Some(e),
+            PgnError::InvalidFen(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for PgnError {
+    fn from(e: io::Error) -> Self {
+        PgnError::Io(e)
+    }
+}
+
+/// A game read from a PGN file
+#[derive(Debug, Clone)]
+pub struct PgnGame {
+    /// Tags describing the game
+    pub tags: GameTags,
+
+    /// Board after playing all the moves of the game, from which the move
+    /// history is available
+    pub board: Board,
+}
+
+impl PgnGame {
+    /// Create a game from a board, with the result taken from the state of
+    /// the game, and a `FEN` tag if it didn't start from the usual starting
+    /// position
+    pub fn from_board(board: Board) -> Self {
+        let mut tags = GameTags {
+            result: PgnResult::from(&board.clone().get_game_state()),
+            ..Default::default()
+        };
+        let mut start = board.clone();
+        start.resume();
+        while start.undo_turn().is_some() {}
+        let fen = start.to_fen();
+        if fen != Board::from_start().to_fen() {
+            tags.set("SetUp", "1".to_string());
+            tags.set("FEN", fen);
+        }
+        Self { tags, board }
+    }
+}
+
+impl Display for PgnGame {
+    /// Write the game in PGN format
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "{}", self.tags)?;
+        // The result is taken from the tags rather than the board, which
+        // doesn't know why a game from a file ended
+        let mut movetext = String::new();
+        self.board.write_turns(&mut movetext)?;
+        if !movetext.is_empty() {
+            write!(f, "{} ", movetext)?;
+        }
+        writeln!(f, "{}", self.tags.result)
+    }
+}
+
+/// Reads games one at a time from a PGN file, without loading the whole
+/// file into memory.
+///
+/// Each item is either a game, or an error describing why a game couldn't
+/// be read. Malformed games are skipped, so reading can continue after an
+/// error, other than errors reading the underlying reader, after which
+/// iteration stops.
+///
+/// This needs the `std` feature.
+#[cfg(feature = "std")]
+pub struct PgnReader<R: BufRead> {
+    reader: R,
+
+    /// A line that was read, but belongs to the next game, along with its
+    /// line number
+    pending: Option<(usize, String)>,
+
+    /// Whether the underlying reader has failed or been exhausted
+    pub(super) done: bool,
+
+    /// Number of lines read so far
+    lines: usize,
+
+    /// Line number of the first line of the last game read
+    pub(super) game_line: usize,
+
+    /// Whether lines that aren't valid UTF-8 are read with the invalid
+    /// bytes replaced, rather than failing
+    pub(super) lossy: bool,
+
+    /// Line numbers of the lines that weren't valid UTF-8, when `lossy` is
+    /// set
+    pub(super) invalid_utf8: Vec<usize>,
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            pending: None,
+            done: false,
+            lines: 0,
+            game_line: 0,
+            lossy: false,
+            invalid_utf8: Vec::new(),
+        }
+    }
+
+    /// Read the next line, without its line ending, along with its line
+    /// number, or `None` at the end of the file
+    fn next_line(&mut self) -> io::Result<Option<(usize, String)>> {
+        if let Some(line) = self.pending.take() {
+            return Ok(Some(line));
+        }
+        let mut bytes = Vec::new();
+        if self.reader.read_until(b'\n', &mut bytes)? == 0 {
+            return Ok(None);
+        }
+        self.lines += 1;
+        let line = match String::from_utf8(bytes) {
+            Ok(line) => line,
+            Err(e) if self.lossy => {
+                self.invalid_utf8.push(self.lines);
+                String::from_utf8_lossy(e.as_bytes()).into_owned()
+            }
+            Err(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "stream did not contain valid UTF-8",
+                ))
+            }
+        };
+        Ok(Some((
+            self.lines,
+            line.trim_end_matches(['\r', '\n']).to_string(),
+        )))
+    }
+
+    /// Read the tag lines and movetext of the next game, or `None` at the
+    /// end of the file
+    pub(super) fn read_game_text(&mut self) -> io::Result<Option<(Vec<String>, String)>> {
+        let mut tags = Vec::new();
+        let mut movetext = String::new();
+        while let Some((number, line)) = self.next_line()? {
+            let trimmed = line.trim();
+            if !trimmed.is_empty() && tags.is_empty() && movetext.trim().is_empty() {
+                self.game_line = number;
+            }
+            if trimmed.starts_with('[') && !in_comment(&movetext) {
+                if !movetext.trim().is_empty() {
+                    // Start of the next game
+                    self.pending = Some((number, line));
+                    break;
+                }
+                tags.push(trimmed.to_string());
+            } else if trimmed.starts_with('%') {
+                // Escaped line, ignore it
+            } else {
+                movetext.push_str(&line);
+                movetext.push('\n');
+            }
+        }
+        if tags.is_empty() && movetext.trim().is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some((tags, movetext)))
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<PgnGame, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_game_text() {
+            Ok(Some((tags, movetext))) => Some(parse_game(&tags, &movetext)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e.into()))
+            }
+        }
+    }
+}
+
+/// Returns whether the given movetext ends inside a `{}` comment
+#[cfg(feature = "std")]
+fn in_comment(movetext: &str) -> bool {
+    movetext.matches('{').count() > movetext.matches('}').count()
+}
+
+/// Parse a tag pair of the form `[Name "Value"]`
+#[cfg(feature = "std")]
+pub(super) fn parse_tag(line: &str) -> Result<(String, String), PgnError> {
+    let invalid = || PgnError::InvalidTag(line.to_string());
+    let inner = line
+        .strip_prefix('[')
+        .and_then(|l| l.strip_suffix(']'))
+        .ok_or_else(invalid)?
+        .trim();
+    let (name, value) = inner.split_once(char::is_whitespace).ok_or_else(invalid)?;
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+    Ok((
+        name.to_string(),
+        value.replace("\\\"", "\"").replace("\\\\", "\\"),
+    ))
+}
+
+/// Remove comments, variations and numeric annotation glyphs from movetext,
+/// leaving only move numbers, moves and the result
+#[cfg(feature = "std")]
+pub(super) fn strip_movetext(movetext: &str) -> String {
+    let mut stripped = String::new();
+    let mut variation_depth: usize = 0;
+    let mut chars = movetext.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                }
+                stripped.push(' ');
+            }
+            ';' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                stripped.push(' ');
+            }
+            '(' => variation_depth += 1,
+            ')' => {
+                variation_depth = variation_depth.saturating_sub(1);
+                stripped.push(' ');
+            }
+            '$' => {
+                while chars.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    chars.next();
+                }
+                stripped.push(' ');
+            }
+            _ if variation_depth > 0 => {}
+            _ => stripped.push(c),
+        }
+    }
+    stripped
+}
+
+#[cfg(feature = "std")]
+pub(super) fn parse_game(tag_lines: &[String], movetext: &str) -> Result<PgnGame, PgnError> {
+    let tags = tag_lines
+        .iter()
+        .map(|line| parse_tag(line))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let tags = GameTags::from_pairs(tags);
+
+    let mut board = match tags.get("FEN") {
+        Some(fen) => Board::from_fen(&fen).map_err(PgnError::InvalidFen)?,
+        None => Board::from_start(),
+    };
+
+    let movetext = strip_movetext(movetext);
+    if let Err(i) = board.apply_movetext(&movetext) {
+        let token = movetext.split_whitespace().nth(i).unwrap_or_default();
+        return Err(PgnError::IllegalMove(token.to_string()));
+    }
+
+    Ok(PgnGame { tags, board })
+}
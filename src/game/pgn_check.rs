@@ -0,0 +1,233 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::{error::Error, fmt::Display};
+use std::io::BufRead;
+
+use super::{
+    pgn::{parse_game, parse_tag, strip_movetext},
+    GameState, GameTags, ParsePgnDateError, PgnDate, PgnError, PgnReader, PgnResult,
+};
+
+/// Tags of the Seven Tag Roster, which every game should have
+const SEVEN_TAG_ROSTER: [&str; 7] = ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+/// Problem with a game found by `PgnValidator`
+#[derive(Debug)]
+pub enum PgnProblem {
+    /// The game couldn't be read, such as for an invalid tag pair or an
+    /// illegal move
+    Unreadable(PgnError),
+
+    /// A tag of the Seven Tag Roster wasn't given
+    /// Includes the name of the tag
+    MissingTag(&'static str),
+
+    /// A tag was given more than once
+    /// Includes the name of the tag
+    DuplicateTag(String),
+
+    /// The `Date` tag isn't of the form `YYYY.MM.DD`
+    InvalidDate(ParsePgnDateError),
+
+    /// The `Result` tag isn't `1-0`, `0-1`, `1/2-1/2` or `*`
+    /// Includes the value of the tag
+    InvalidResult(String),
+
+    /// The movetext doesn't end with a result
+    MissingResult,
+
+    /// The result at the end of the movetext isn't the one in the `Result`
+    /// tag
+    /// Includes the result in the tag, then the one in the movetext
+    ResultMismatch(PgnResult, PgnResult),
+
+    /// The game ended on the board, by checkmate or a draw, with a
+    /// different result from the one in the `Result` tag
+    /// Includes the result in the tag, then the one on the board
+    WrongResult(PgnResult, PgnResult),
+
+    /// A line of the game isn't valid UTF-8
+    /// Includes the line number
+    InvalidUtf8(usize),
+}
+
+impl Display for PgnProblem {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PgnProblem::Unreadable(e) => write!(f, "{}", e),
+            PgnProblem::MissingTag(name) => write!(f, "missing {} tag", name),
+            PgnProblem::DuplicateTag(name) => write!(f, "{} tag given more than once", name),
+            PgnProblem::InvalidDate(e) => write!(f, "{}", e),
+            PgnProblem::InvalidResult(value) => write!(f, "invalid result '{}'", value),
+            PgnProblem::MissingResult => write!(f, "movetext doesn't end with a result"),
+            PgnProblem::ResultMismatch(tag, movetext) => write!(
+                f,
+                "movetext ends with {}, but the Result tag is {}",
+                movetext, tag
+            ),
+            PgnProblem::WrongResult(tag, board) => write!(
+                f,
+                "the game ended {} on the board, but the Result tag is {}",
+                board, tag
+            ),
+            PgnProblem::InvalidUtf8(line) => write!(f, "line {} isn't valid UTF-8", line),
+        }
+    }
+}
+
+impl Error for PgnProblem {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PgnProblem::Unreadable(e) => Some(e),
+            PgnProblem::InvalidDate(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Every problem found with a game
+#[derive(Debug)]
+pub struct PgnCheck {
+    /// Line number of the first line of the game
+    pub line: usize,
+
+    /// Tags of the game, as far as they could be read
+    pub tags: GameTags,
+
+    /// Problems found with the game, which is empty if there were none
+    pub problems: Vec<PgnProblem>,
+}
+
+/// Checks the games of a PGN file one at a time, strictly, for maintaining
+/// databases.
+///
+/// Where `PgnReader` reads whatever it can, this reports each game's
+/// problems: tag pairs, moves and FEN tags that can't be read, missing,
+/// repeated or invalid tags, results that disagree with the movetext or
+/// the final position, and lines that aren't valid UTF-8, which are read
+/// with the invalid bytes replaced so that checking can continue.
+///
+/// This needs the `std` feature.
+pub struct PgnValidator<R: BufRead> {
+    reader: PgnReader<R>,
+}
+
+impl<R: BufRead> PgnValidator<R> {
+    pub fn new(reader: R) -> Self {
+        let mut reader = PgnReader::new(reader);
+        reader.lossy = true;
+        Self { reader }
+    }
+}
+
+impl<R: BufRead> Iterator for PgnValidator<R> {
+    type Item = PgnCheck;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reader.done {
+            return None;
+        }
+        let (tag_lines, movetext) = match self.reader.read_game_text() {
+            Ok(Some(game)) => game,
+            Ok(None) => {
+                self.reader.done = true;
+                return None;
+            }
+            Err(e) => {
+                self.reader.done = true;
+                return Some(PgnCheck {
+                    line: self.reader.game_line,
+                    tags: GameTags::default(),
+                    problems: vec![PgnProblem::Unreadable(e.into())],
+                });
+            }
+        };
+        let mut problems: Vec<PgnProblem> = self
+            .reader
+            .invalid_utf8
+            .drain(..)
+            .map(PgnProblem::InvalidUtf8)
+            .collect();
+        let tags = check_tags(&tag_lines, &mut problems);
+        check_movetext(&tag_lines, &movetext, &tags, &mut problems);
+        Some(PgnCheck {
+            line: self.reader.game_line,
+            tags,
+            problems,
+        })
+    }
+}
+
+/// Check the tag pairs of a game, returning the tags that could be read
+fn check_tags(tag_lines: &[String], problems: &mut Vec<PgnProblem>) -> GameTags {
+    let mut pairs: Vec<(String, String)> = Vec::new();
+    for line in tag_lines {
+        match parse_tag(line) {
+            Ok((name, _)) if pairs.iter().any(|(other, _)| *other == name) => {
+                problems.push(PgnProblem::DuplicateTag(name));
+            }
+            Ok(pair) => pairs.push(pair),
+            Err(e) => problems.push(PgnProblem::Unreadable(e)),
+        }
+    }
+    let value = |name: &str| {
+        pairs
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    };
+    for name in SEVEN_TAG_ROSTER {
+        if value(name).is_none() {
+            problems.push(PgnProblem::MissingTag(name));
+        }
+    }
+    if let Some(Err(e)) = value("Date").map(str::parse::<PgnDate>) {
+        problems.push(PgnProblem::InvalidDate(e));
+    }
+    if let Some(result) = value("Result").filter(|result| result.parse::<PgnResult>().is_err()) {
+        problems.push(PgnProblem::InvalidResult(result.into()));
+    }
+    GameTags::from_pairs(pairs)
+}
+
+/// Check that the moves of a game can be played, and that its result
+/// agrees with them
+fn check_movetext(
+    tag_lines: &[String],
+    movetext: &str,
+    tags: &GameTags,
+    problems: &mut Vec<PgnProblem>,
+) {
+    // Only the tags that could be read, which were already checked
+    let readable: Vec<String> = tag_lines
+        .iter()
+        .filter(|line| parse_tag(line).is_ok())
+        .cloned()
+        .collect();
+    let mut game = match parse_game(&readable, movetext) {
+        Ok(game) => game,
+        Err(e) => {
+            problems.push(PgnProblem::Unreadable(e));
+            return;
+        }
+    };
+    match strip_movetext(movetext)
+        .split_whitespace()
+        .last()
+        .and_then(|token| token.parse::<PgnResult>().ok())
+    {
+        None => problems.push(PgnProblem::MissingResult),
+        Some(result) if result != tags.result => {
+            problems.push(PgnProblem::ResultMismatch(tags.result, result));
+        }
+        Some(_) => {}
+    }
+    // Games can also end by resignation, time or agreement, which the
+    // board doesn't know about
+    let state = game.board.get_game_state();
+    if state != GameState::Playing && PgnResult::from(&state) != tags.result {
+        problems.push(PgnProblem::WrongResult(
+            tags.result,
+            PgnResult::from(&state),
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use core::fmt::Display;
 
 use super::{Board, Color, Position};
 
@@ -13,6 +13,16 @@ pub enum PieceType {
     Pawn,
 }
 
+/// All kinds of pieces, in the order given by `PieceType::index`
+pub const PIECE_TYPES: [PieceType; 6] = [
+    PieceType::King,
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
 pub const PROMOTABLE_TYPES: [PieceType; 4] = [
     PieceType::Queen,
     PieceType::Rook,
@@ -32,8 +42,49 @@ pub const KNIGHT_MOVES: [(i8, i8); 8] = [
     (2, -1),
 ];
 
+impl PieceType {
+    /// Returns a unique index from 0..6 for this kind of piece, for use in
+    /// tables
+    pub fn index(self) -> usize {
+        match self {
+            PieceType::King => 0,
+            PieceType::Queen => 1,
+            PieceType::Rook => 2,
+            PieceType::Bishop => 3,
+            PieceType::Knight => 4,
+            PieceType::Pawn => 5,
+        }
+    }
+
+    /// Returns the usual value of this kind of piece, counted in pawns. The
+    /// king is given no value, as it can't be traded.
+    pub fn value(self) -> u32 {
+        match self {
+            PieceType::King => 0,
+            PieceType::Queen => 9,
+            PieceType::Rook => 5,
+            PieceType::Bishop => 3,
+            PieceType::Knight => 3,
+            PieceType::Pawn => 1,
+        }
+    }
+
+    /// Returns the uppercase letter used for this kind of piece in FEN and
+    /// SAN
+    pub fn letter(self) -> char {
+        match self {
+            PieceType::King => 'K',
+            PieceType::Queen => 'Q',
+            PieceType::Rook => 'R',
+            PieceType::Bishop => 'B',
+            PieceType::Knight => 'N',
+            PieceType::Pawn => 'P',
+        }
+    }
+}
+
 impl Display for PieceType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{}",
@@ -50,20 +101,15 @@ impl Display for PieceType {
 }
 
 /// Represents a piece on the board
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Piece {
     pub kind: PieceType,
     pub color: Color,
-    pub move_count: i32,
 }
 
 impl Piece {
     pub fn new(kind: PieceType, color: Color) -> Self {
-        Self {
-            kind,
-            color,
-            move_count: 0,
-        }
+        Self { kind, color }
     }
 
     /// Returns whether the piece could move here on an empty board.
@@ -91,7 +137,7 @@ impl Piece {
     }
 
     fn could_rook_move_to(&self, from: Position, to: Position) -> bool {
-        from.row() == to.row() || from.col() == to.row()
+        from.row() == to.row() || from.col() == to.col()
     }
 
     fn could_bishop_move_to(&self, from: Position, to: Position) -> bool {
@@ -116,7 +162,7 @@ impl Piece {
         if col_diff >= 2 {
             return false;
         }
-        let row_diff = from.row() - to.row();
+        let row_diff = to.row() - from.row();
         // If they're moving in the wrong direction
         if row_diff * self.color.get_direction() <= 0 {
             return false;
@@ -152,7 +198,7 @@ impl Piece {
 }
 
 impl Display for Piece {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} {}", self.color, self.kind)?;
         Ok(())
     }
@@ -1,7 +1,29 @@
-use std::fmt::{Debug, Display};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    error::Error,
+    fmt::{Debug, Display},
+    str::FromStr,
+};
 
 use super::{board::FenError, Color};
 
+/// Error when parsing a square name such as `e4`
+///
+/// Includes the string that failed to parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePositionError(pub String);
+
+impl Display for ParsePositionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid square '{}', expected a file and a rank", self.0)
+    }
+}
+
+impl Error for ParsePositionError {}
+
 /// Represents a position on the chess board
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Position(i8);
@@ -18,21 +40,10 @@ impl Position {
         if fen_pos == "-" {
             return Ok(None);
         }
-        let chars: Vec<char> = fen_pos.chars().collect();
-        if chars.len() != 2 {
-            return Err(FenError::InvalidPosition(fen_pos.to_string()));
-        }
-        let col_char = chars[0].to_ascii_lowercase();
-        let row_char = chars[1];
-
-        if !('a'..='h').contains(&col_char) || !('1'..='8').contains(&row_char) {
-            return Err(FenError::InvalidPosition(fen_pos.to_string()));
-        }
-
-        let row = row_char as u8 - b'1';
-        let col = col_char as u8 - b'a';
-
-        Ok(Some(Self::new(row as i8, col as i8)))
+        fen_pos
+            .parse()
+            .map(Some)
+            .map_err(|_| FenError::InvalidPosition(fen_pos.to_string()))
     }
 
     /// Position from 0..64, for indexing into a board
@@ -45,7 +56,7 @@ impl Position {
         self.row() + 1
     }
 
-    /// Rank (row), as 0 - 8
+    /// Rank (row), as 0-7
     pub fn row(&self) -> i8 {
         self.0 / 8
     }
@@ -55,17 +66,16 @@ impl Position {
         (self.col() as u8 + b'A') as char
     }
 
-    /// File (column), as 0 - 8
+    /// File (column), as 0-7
     pub fn col(&self) -> i8 {
         self.0 % 8
     }
 
-    // Color of the square
+    /// Color of the square, where A1 is black
     pub fn color(&self) -> Color {
-        match self.0 % 2 {
+        match (self.row() + self.col()) % 2 {
             0 => Color::Black,
-            1 => Color::White,
-            _ => panic!("Bruh"),
+            _ => Color::White,
         }
     }
 
@@ -89,14 +99,45 @@ impl From<i8> for Position {
     }
 }
 
+impl FromStr for Position {
+    type Err = ParsePositionError;
+
+    /// Parse a square name, such as `e4`. The file may be in either case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = s.chars().collect();
+        if chars.len() != 2 {
+            return Err(ParsePositionError(s.to_string()));
+        }
+        let col_char = chars[0].to_ascii_lowercase();
+        let row_char = chars[1];
+
+        if !('a'..='h').contains(&col_char) || !('1'..='8').contains(&row_char) {
+            return Err(ParsePositionError(s.to_string()));
+        }
+
+        let row = row_char as u8 - b'1';
+        let col = col_char as u8 - b'a';
+
+        Ok(Self::new(row as i8, col as i8))
+    }
+}
+
+impl TryFrom<&str> for Position {
+    type Error = ParsePositionError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
 impl Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}{}", self.file(), self.rank())
     }
 }
 
 impl Debug for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Position(row={}, col={})", self.row(), self.col())
     }
 }
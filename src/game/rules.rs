@@ -0,0 +1,97 @@
+use core::{error::Error, fmt::Display};
+
+/// Settings for when a game ends, which differ between organizations.
+///
+/// The 75 move rule and fivefold repetition always end the game, as they
+/// don't need to be claimed. The limits here set when the game ends before
+/// that. Draws that a player would have to claim, such as by the 50 move
+/// rule or threefold repetition, are treated as automatic: the game ends as
+/// soon as a limit is reached, as if the draw was claimed straight away.
+/// To let players play on, use higher limits, such as `without_claims`,
+/// and claim draws with `Board::claim_draw`.
+///
+/// The fields aren't checked when set directly. Use `Rules::new` to reject
+/// limits that would draw the game in its first position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    /// Number of full moves without a capture or pawn push after which the
+    /// game is drawn, which should be at least 1
+    pub move_limit: u32,
+
+    /// Number of times a position must occur for the game to be drawn,
+    /// which should be at least 2
+    pub repetition_limit: usize,
+
+    /// Whether running out of time is a draw rather than a loss when the
+    /// opponent doesn't have enough material to ever checkmate
+    pub timeout_insufficient_material_draw: bool,
+}
+
+impl Default for Rules {
+    /// Draws are claimed by the 50 move rule and threefold repetition
+    fn default() -> Self {
+        Self {
+            move_limit: 50,
+            repetition_limit: 3,
+            timeout_insufficient_material_draw: true,
+        }
+    }
+}
+
+impl Rules {
+    /// Create rules with the given limits, checking that neither draws the
+    /// game before a move is made
+    pub fn new(
+        move_limit: u32,
+        repetition_limit: usize,
+        timeout_insufficient_material_draw: bool,
+    ) -> Result<Self, RulesError> {
+        if move_limit < 1 {
+            return Err(RulesError::MoveLimit(move_limit));
+        }
+        if repetition_limit < 2 {
+            return Err(RulesError::RepetitionLimit(repetition_limit));
+        }
+        Ok(Self {
+            move_limit,
+            repetition_limit,
+            timeout_insufficient_material_draw,
+        })
+    }
+
+    /// Rules where draws are never claimed, so the game only ends by the 75
+    /// move rule or fivefold repetition, as when no arbiter is present
+    pub fn without_claims() -> Self {
+        Self {
+            move_limit: 75,
+            repetition_limit: 5,
+            ..Default::default()
+        }
+    }
+}
+
+/// Limit given to `Rules::new` that would draw the game straight away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RulesError {
+    /// The move limit is less than 1
+    MoveLimit(u32),
+
+    /// The repetition limit is less than 2, so the first position would
+    /// already count as repeated
+    RepetitionLimit(usize),
+}
+
+impl Display for RulesError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RulesError::MoveLimit(limit) => {
+                write!(f, "move limit must be at least 1, not {}", limit)
+            }
+            RulesError::RepetitionLimit(limit) => {
+                write!(f, "repetition limit must be at least 2, not {}", limit)
+            }
+        }
+    }
+}
+
+impl Error for RulesError {}
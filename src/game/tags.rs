@@ -0,0 +1,236 @@
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{error::Error, fmt::Display, str::FromStr};
+
+use super::{Color, GameState};
+
+/// Date of a game, as given in a PGN `Date` tag, such as `2021.07.??`.
+///
+/// Any part of the date may be unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PgnDate {
+    pub year: Option<u16>,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+}
+
+/// Error when parsing a PGN date
+///
+/// Includes the string that failed to parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePgnDateError(pub String);
+
+impl Display for ParsePgnDateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid date '{}', expected YYYY.MM.DD", self.0)
+    }
+}
+
+impl Error for ParsePgnDateError {}
+
+impl FromStr for PgnDate {
+    type Err = ParsePgnDateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParsePgnDateError(s.to_string());
+        let parts: Vec<&str> = s.split('.').collect();
+        if parts.len() != 3 || parts[0].len() != 4 || parts[1].len() != 2 || parts[2].len() != 2 {
+            return Err(invalid());
+        }
+        // Each part is either all question marks or a number
+        fn part<T: FromStr>(s: &str) -> Result<Option<T>, ()> {
+            if s.chars().all(|c| c == '?') {
+                Ok(None)
+            } else if s.chars().all(|c| c.is_ascii_digit()) {
+                s.parse().map(Some).map_err(|_| ())
+            } else {
+                Err(())
+            }
+        }
+        let date = PgnDate {
+            year: part(parts[0]).map_err(|_| invalid())?,
+            month: part(parts[1]).map_err(|_| invalid())?,
+            day: part(parts[2]).map_err(|_| invalid())?,
+        };
+        if date.month.is_some_and(|m| !(1..=12).contains(&m))
+            || date.day.is_some_and(|d| !(1..=31).contains(&d))
+        {
+            return Err(invalid());
+        }
+        Ok(date)
+    }
+}
+
+impl Display for PgnDate {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.year {
+            Some(year) => write!(f, "{:04}", year)?,
+            None => write!(f, "????")?,
+        }
+        for part in [self.month, self.day] {
+            match part {
+                Some(n) => write!(f, ".{:02}", n)?,
+                None => write!(f, ".??")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Result of a game, as given in a PGN `Result` tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PgnResult {
+    /// `1-0`
+    WhiteWins,
+    /// `0-1`
+    BlackWins,
+    /// `1/2-1/2`
+    Draw,
+    /// `*`, the game is ongoing or the result is unknown
+    #[default]
+    Unknown,
+}
+
+impl From<&GameState> for PgnResult {
+    fn from(state: &GameState) -> Self {
+        match state {
+            GameState::Playing => PgnResult::Unknown,
+            GameState::Win(Color::White, _) => PgnResult::WhiteWins,
+            GameState::Win(Color::Black, _) => PgnResult::BlackWins,
+            GameState::Draw(_) => PgnResult::Draw,
+        }
+    }
+}
+
+impl FromStr for PgnResult {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1-0" => Ok(PgnResult::WhiteWins),
+            "0-1" => Ok(PgnResult::BlackWins),
+            "1/2-1/2" => Ok(PgnResult::Draw),
+            "*" => Ok(PgnResult::Unknown),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for PgnResult {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PgnResult::WhiteWins => "1-0",
+                PgnResult::BlackWins => "0-1",
+                PgnResult::Draw => "1/2-1/2",
+                PgnResult::Unknown => "*",
+            }
+        )
+    }
+}
+
+/// Tags describing a game in a PGN file.
+///
+/// The Seven Tag Roster is stored in typed fields, and any other tags are
+/// kept in order in `extra`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameTags {
+    pub event: String,
+    pub site: String,
+    pub date: PgnDate,
+    pub round: String,
+    pub white: String,
+    pub black: String,
+    pub result: PgnResult,
+
+    /// Any other tags, such as `FEN` or `TimeControl`
+    pub extra: Vec<(String, String)>,
+}
+
+impl Default for GameTags {
+    fn default() -> Self {
+        Self {
+            event: "?".to_string(),
+            site: "?".to_string(),
+            date: PgnDate::default(),
+            round: "?".to_string(),
+            white: "?".to_string(),
+            black: "?".to_string(),
+            result: PgnResult::Unknown,
+            extra: vec![],
+        }
+    }
+}
+
+impl GameTags {
+    /// Create tags from a list of name/value pairs
+    pub fn from_pairs(pairs: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut tags = Self::default();
+        for (name, value) in pairs {
+            tags.set(&name, value);
+        }
+        tags
+    }
+
+    /// Returns the tags as a list of name/value pairs, starting with the
+    /// Seven Tag Roster, in the order they should be exported
+    pub fn to_pairs(&self) -> Vec<(String, String)> {
+        let mut pairs = vec![
+            ("Event".to_string(), self.event.clone()),
+            ("Site".to_string(), self.site.clone()),
+            ("Date".to_string(), self.date.to_string()),
+            ("Round".to_string(), self.round.clone()),
+            ("White".to_string(), self.white.clone()),
+            ("Black".to_string(), self.black.clone()),
+            ("Result".to_string(), self.result.to_string()),
+        ];
+        pairs.extend(self.extra.iter().cloned());
+        pairs
+    }
+
+    /// Returns the value of the tag with the given name, if it was given
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.to_pairs()
+            .into_iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Set the value of the tag with the given name
+    pub fn set(&mut self, name: &str, value: String) {
+        match name {
+            "Event" => self.event = value,
+            "Site" => self.site = value,
+            "Round" => self.round = value,
+            "White" => self.white = value,
+            "Black" => self.black = value,
+            // Invalid dates and results are treated as unknown
+            "Date" => self.date = value.parse().unwrap_or_default(),
+            "Result" => self.result = value.parse().unwrap_or_default(),
+            _ => match self.extra.iter_mut().find(|(tag, _)| tag == name) {
+                Some((_, v)) => *v = value,
+                None => self.extra.push((name.to_string(), value)),
+            },
+        }
+    }
+}
+
+impl Display for GameTags {
+    /// Write the tag pair section of a PGN file
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (name, value) in self.to_pairs() {
+            writeln!(
+                f,
+                "[{} \"{}\"]",
+                name,
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            )?;
+        }
+        Ok(())
+    }
+}
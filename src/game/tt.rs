@@ -0,0 +1,186 @@
+use alloc::{vec, vec::Vec};
+use core::mem::size_of;
+
+use super::Move;
+use crate::search::{MATE, MAX_DEPTH};
+
+/// Number of entries in each bucket of the table
+const BUCKET_SIZE: usize = 4;
+
+/// How a stored score relates to the true score of the position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is exact
+    Exact,
+
+    /// The true score is at least this, as the search failed high
+    Lower,
+
+    /// The true score is at most this, as the search failed low
+    Upper,
+}
+
+/// Convert a score found `ply` plies from the root into the form stored in
+/// the table, where mate scores count plies from the stored position rather
+/// than from the root, so that they stay right when the position is reached
+/// by a line of a different length
+pub fn score_to_tt(score: i32, ply: u32) -> i32 {
+    if score >= MATE - MAX_DEPTH as i32 {
+        score + ply as i32
+    } else if score <= -(MATE - MAX_DEPTH as i32) {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+/// Convert a score stored in the table back into one for a position `ply`
+/// plies from the root, undoing `score_to_tt`
+pub fn score_from_tt(score: i32, ply: u32) -> i32 {
+    if score >= MATE - MAX_DEPTH as i32 {
+        score - ply as i32
+    } else if score <= -(MATE - MAX_DEPTH as i32) {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+/// Result of searching a position, as stored in a `TranspositionTable`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TtEntry {
+    /// Zobrist hash of the position
+    pub key: u64,
+
+    /// Depth that the position was searched to
+    pub depth: u8,
+
+    pub score: i32,
+    pub bound: Bound,
+
+    /// Best move found, if any
+    pub best_move: Option<Move>,
+
+    /// Search that stored this entry, used to replace stale entries first
+    generation: u8,
+}
+
+/// Fixed-size table of search results, indexed by Zobrist hash.
+///
+/// Each hash maps to a bucket of entries. When a bucket is full, an entry
+/// from an earlier search is replaced first, otherwise the shallowest entry
+/// is replaced, as deeper results are more expensive to recompute.
+#[derive(Debug, Clone)]
+pub struct TranspositionTable {
+    buckets: Vec<[Option<TtEntry>; BUCKET_SIZE]>,
+    generation: u8,
+}
+
+impl TranspositionTable {
+    /// Create a table using roughly the given number of megabytes
+    pub fn new(megabytes: usize) -> Self {
+        let bucket_bytes = size_of::<[Option<TtEntry>; BUCKET_SIZE]>();
+        Self::with_buckets(megabytes * 1024 * 1024 / bucket_bytes)
+    }
+
+    /// Create a table with the given number of buckets, rounded down to a
+    /// power of two so that indexing is a mask
+    pub fn with_buckets(buckets: usize) -> Self {
+        let buckets = if buckets == 0 {
+            1
+        } else {
+            1 << buckets.ilog2()
+        };
+        Self {
+            buckets: vec![Default::default(); buckets],
+            generation: 0,
+        }
+    }
+
+    /// Mark the start of a new search, so that entries from earlier searches
+    /// are replaced before those from this one
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Remove every entry
+    pub fn clear(&mut self) {
+        self.buckets.fill(Default::default());
+        self.generation = 0;
+    }
+
+    fn bucket_index(&self, key: u64) -> usize {
+        key as usize & (self.buckets.len() - 1)
+    }
+
+    /// Returns the entry for the position with the given hash, if there is
+    /// one
+    pub fn probe(&self, key: u64) -> Option<&TtEntry> {
+        self.buckets[self.bucket_index(key)]
+            .iter()
+            .flatten()
+            .find(|entry| entry.key == key)
+    }
+
+    /// Store the result of searching the position with the given hash
+    pub fn store(
+        &mut self,
+        key: u64,
+        depth: u8,
+        score: i32,
+        bound: Bound,
+        best_move: Option<Move>,
+    ) {
+        let generation = self.generation;
+        let index = self.bucket_index(key);
+        let bucket = &mut self.buckets[index];
+
+        // Replace the entry for the same position, then an empty slot, then
+        // the least valuable entry
+        let slot = match bucket
+            .iter()
+            .position(|entry| entry.as_ref().is_some_and(|e| e.key == key))
+            .or_else(|| bucket.iter().position(Option::is_none))
+        {
+            Some(i) => i,
+            None => (0..BUCKET_SIZE)
+                .min_by_key(|&i| {
+                    let entry = bucket[i].as_ref().unwrap();
+                    (entry.generation == generation, entry.depth)
+                })
+                .unwrap(),
+        };
+
+        // Keep the previous best move if this search didn't find one
+        let best_move = best_move.or_else(|| {
+            bucket[slot]
+                .as_ref()
+                .filter(|e| e.key == key)
+                .and_then(|e| e.best_move)
+        });
+        bucket[slot] = Some(TtEntry {
+            key,
+            depth,
+            score,
+            bound,
+            best_move,
+            generation,
+        });
+    }
+
+    /// Returns the proportion of slots in use, in thousandths, as reported by
+    /// UCI engines, sampling the first thousand buckets
+    pub fn hashfull(&self) -> usize {
+        let sampled = &self.buckets[..self.buckets.len().min(1000)];
+        let used = sampled
+            .iter()
+            .flatten()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .is_some_and(|e| e.generation == self.generation)
+            })
+            .count();
+        used * 1000 / (sampled.len() * BUCKET_SIZE)
+    }
+}
@@ -1,9 +1,9 @@
-use std::fmt::Display;
+use core::fmt::Display;
 
 use super::{PieceType, Position};
 
 /// Represents a move that can be made
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Turn {
     /// Kind of piece being moved
     pub kind: PieceType,
@@ -129,7 +129,7 @@ impl Turn {
 }
 
 impl Display for Turn {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{} from {} to {}", self.kind, self.from, self.to)?;
         if let Some((add_to, add_from)) = self.additional_move {
             write!(f, ", additionally moving {} to {}", add_from, add_to)?;
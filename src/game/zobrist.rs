@@ -0,0 +1,70 @@
+//! Random keys for Zobrist hashing.
+//!
+//! A position is hashed by XORing together a key for each piece on each
+//! square, along with keys for the player to move, the castling rights and
+//! the en passant file. As XOR undoes itself, the hash can be updated as
+//! pieces move rather than being recomputed from scratch.
+
+use super::{CastlingRights, CastlingSide, Color, PieceType, Position};
+
+/// Generate `N` pseudo-random keys at compile time, using SplitMix64
+const fn keys<const N: usize>(seed: u64) -> [u64; N] {
+    let mut keys = [0; N];
+    let mut state = seed;
+    let mut i = 0;
+    while i < N {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+/// Keys for each piece on each square, indexed by color, then kind, then
+/// square
+const PIECE_KEYS: [u64; 2 * 6 * 64] = keys(1);
+
+/// Keys for each castling rook column, indexed by color, then side, then
+/// column
+const CASTLING_KEYS: [u64; 2 * 2 * 8] = keys(2);
+
+/// Keys for the file of the en passant target
+const EN_PASSANT_KEYS: [u64; 8] = keys(3);
+
+/// Key included when it is black's turn
+const BLACK_TO_MOVE_KEY: u64 = keys::<1>(4)[0];
+
+/// Returns the key for a piece on a square
+pub fn piece_key(kind: PieceType, color: Color, pos: Position) -> u64 {
+    PIECE_KEYS[(color.index() * 6 + kind.index()) * 64 + pos.pos()]
+}
+
+/// Returns the combined key for the player to move, the castling rights and
+/// the en passant target
+pub fn state_key(
+    whose_turn: Color,
+    castling_rights: CastlingRights,
+    en_passant_target: Option<Position>,
+) -> u64 {
+    let mut key = 0;
+    if whose_turn == Color::Black {
+        key ^= BLACK_TO_MOVE_KEY;
+    }
+    for color in [Color::White, Color::Black] {
+        for (s, side) in [CastlingSide::KingSide, CastlingSide::QueenSide]
+            .into_iter()
+            .enumerate()
+        {
+            if let Some(col) = castling_rights.rook_col(color, side) {
+                key ^= CASTLING_KEYS[(color.index() * 2 + s) * 8 + col as usize];
+            }
+        }
+    }
+    if let Some(target) = en_passant_target {
+        key ^= EN_PASSANT_KEYS[target.col() as usize];
+    }
+    key
+}
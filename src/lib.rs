@@ -0,0 +1,16 @@
+//! Chess rules, move generation and notation.
+//!
+//! Most users only need `prelude`, see the `game` module for the full API.
+//! Without the default `std` feature, this builds for `no_std` targets with
+//! `alloc`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod eval;
+pub mod game;
+pub mod prelude;
+pub mod search;
+#[doc(hidden)]
+pub mod testing;
@@ -1,31 +1,154 @@
-use game::Board;
+use std::{fs::File, io::BufReader, process::ExitCode};
 
-pub mod game;
+use chs::game::{parse_perft_suite, parse_position, perft, Board, MoveList, PgnValidator};
 
-fn num_moves(board: &mut Board, depth: i32) -> i64 {
-    if depth == 1 {
-        // println!("{}", board);
-        return board.get_moves().len() as i64;
-    }
+const USAGE: &str = "usage: chs perft [--fen <fen>] [--depth <depth>] [--divide]
+       chs perft-suite <file> [--depth <max depth>]
+       chs pgn validate <file>";
+
+/// Options for the `perft` subcommand
+struct PerftOptions {
+    /// Position to count from, as FEN, or the starting position if not given
+    fen: Option<String>,
+    depth: u32,
+
+    /// Whether to give the count after each legal move separately
+    divide: bool,
+}
 
-    let mut count = 0;
-    let moves = board.get_moves();
-    for turn in moves {
-        board.make_turn(turn);
-        count += num_moves(board, depth - 1);
-        board.undo_turn().expect("Should be a turn");
+impl PerftOptions {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = PerftOptions {
+            fen: None,
+            depth: 6,
+            divide: false,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--fen" => {
+                    options.fen = Some(args.next().ok_or("--fen needs a position")?);
+                }
+                "--depth" => {
+                    let depth = args.next().ok_or("--depth needs a number")?;
+                    options.depth = depth
+                        .parse()
+                        .map_err(|_| format!("invalid depth '{}'", depth))?;
+                }
+                "--divide" => options.divide = true,
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+        Ok(options)
     }
-    count
 }
 
-fn main() {
-    let depth = 6;
+fn run_perft(options: PerftOptions) -> Result<(), String> {
+    let mut board = match &options.fen {
+        Some(fen) => parse_position(fen).map_err(|e| e.to_string())?,
+        None => Board::from_start(),
+    };
+
+    let total = if options.divide && options.depth > 0 {
+        let mut moves = MoveList::new();
+        board.generate_moves(&mut moves);
+        let mut total = 0;
+        for turn in moves {
+            let uci = board.turn_to_uci(&turn);
+            board.make_turn(turn);
+            let count = perft(&mut board, options.depth - 1);
+            board.undo_turn();
+            println!("{}: {}", uci, count);
+            total += count;
+        }
+        println!();
+        total
+    } else {
+        perft(&mut board, options.depth)
+    };
+
+    println!("Nodes searched: {}", total);
+    Ok(())
+}
+
+/// Check move generation against every record of a perft suite file,
+/// stopping at the first count that doesn't match
+fn run_perft_suite(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let path = args.next().ok_or(USAGE)?;
+    let mut max_depth = u32::MAX;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--depth" => {
+                let depth = args.next().ok_or("--depth needs a number")?;
+                max_depth = depth
+                    .parse()
+                    .map_err(|_| format!("invalid depth '{}'", depth))?;
+            }
+            _ => return Err(format!("unknown argument '{}'", arg)),
+        }
+    }
 
-    let mut board = Board::from_start();
+    let suite = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let records = parse_perft_suite(&suite).map_err(|e| e.to_string())?;
+    for record in &records {
+        record.verify(max_depth).map_err(|e| e.to_string())?;
+        println!("ok {}", record.fen);
+    }
+    println!("All {} positions match", records.len());
+    Ok(())
+}
 
-    let num = num_moves(&mut board, depth);
+/// Check every game of a PGN file, giving each problem found with each
+/// game, and failing if there were any
+fn run_pgn_validate(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let path = args.next().ok_or(USAGE)?;
+    if let Some(arg) = args.next() {
+        return Err(format!("unknown argument '{}'", arg));
+    }
 
-    assert!(board.undo_turn().is_none());
+    let file = File::open(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut games = 0;
+    let mut bad_games = 0;
+    for (i, check) in PgnValidator::new(BufReader::new(file)).enumerate() {
+        games += 1;
+        if check.problems.is_empty() {
+            continue;
+        }
+        bad_games += 1;
+        for problem in &check.problems {
+            println!(
+                "{}:{}: game {} ({} - {}): {}",
+                path,
+                check.line,
+                i + 1,
+                check.tags.white,
+                check.tags.black,
+                problem
+            );
+        }
+    }
+    if bad_games > 0 {
+        return Err(format!("{} of {} games have problems", bad_games, games));
+    }
+    println!("All {} games are valid", games);
+    Ok(())
+}
 
-    println!("Num moves at {} ply: {}", depth, num);
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("perft") => PerftOptions::parse(args).and_then(run_perft),
+        Some("perft-suite") => run_perft_suite(args),
+        Some("pgn") => match args.next().as_deref() {
+            Some("validate") => run_pgn_validate(args),
+            _ => Err(USAGE.to_string()),
+        },
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,16 @@
+//! The stable public surface of the crate.
+//!
+//! Importing `chs::prelude::*` gives everything needed to set up positions,
+//! generate and play moves, find out how a game ended, and search for the
+//! best move with any `Engine` or `Evaluator`. These items are kept stable
+//! as the internals are reworked, whereas anything only reachable through
+//! `chs::game`, `chs::eval` or `chs::search` may change between versions.
+
+pub use crate::game::{
+    Board, BoardBuilder, CastlingRights, CastlingSide, ClaimError, Color, DrawReason, FenError,
+    GameState, MoveError, PieceType, Position, Rules, RulesError, SanError, Turn, WinReason,
+};
+pub use crate::{
+    eval::Evaluator,
+    search::{Engine, SearchLimits, SearchResult},
+};
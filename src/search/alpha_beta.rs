@@ -0,0 +1,100 @@
+use crate::{
+    eval::Evaluator,
+    game::{Board, Bound, Turn},
+};
+
+use super::{
+    minimax::{is_search_draw, make_turn, terminal_score, undo_turn},
+    DRAW, MATE,
+};
+
+/// Find the best turn for the player to move, along with its score, using
+/// alpha-beta pruning.
+///
+/// This gives the same result as `minimax`, but skips lines that can't
+/// affect it, so searches far fewer positions. Returns `None` if there are
+/// no legal turns.
+pub fn alpha_beta<E: Evaluator>(
+    board: &mut Board,
+    depth: u32,
+    evaluator: &mut E,
+) -> Option<(Turn, i32)> {
+    evaluator.reset(board);
+    let mut best: Option<(Turn, i32)> = None;
+    let mut alpha = -MATE;
+    for turn in board.get_moves() {
+        make_turn(board, evaluator, turn);
+        let score = -negamax(board, depth.saturating_sub(1), 1, -MATE, -alpha, evaluator);
+        undo_turn(board, evaluator);
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((turn, score));
+            alpha = alpha.max(score);
+        }
+    }
+    best
+}
+
+/// Search the position to the given depth, only looking for scores between
+/// `alpha` and `beta`.
+///
+/// Returns the score found, and whether it is the exact score or only a
+/// bound on it, as needed for storing it in a `TranspositionTable`.
+pub fn alpha_beta_window<E: Evaluator>(
+    board: &mut Board,
+    depth: u32,
+    alpha: i32,
+    beta: i32,
+    evaluator: &mut E,
+) -> (i32, Bound) {
+    evaluator.reset(board);
+    let score = negamax(board, depth, 0, alpha, beta, evaluator);
+    (score, bound(score, alpha, beta))
+}
+
+/// Returns how a score found by searching with the given window relates to
+/// the true score
+pub(super) fn bound(score: i32, alpha: i32, beta: i32) -> Bound {
+    if score <= alpha {
+        Bound::Upper
+    } else if score >= beta {
+        Bound::Lower
+    } else {
+        Bound::Exact
+    }
+}
+
+/// Fail-soft negamax, which may return a score outside the window, where
+/// `ply` is the distance from the root
+fn negamax<E: Evaluator>(
+    board: &mut Board,
+    depth: u32,
+    ply: i32,
+    mut alpha: i32,
+    beta: i32,
+    evaluator: &mut E,
+) -> i32 {
+    if is_search_draw(board) {
+        return DRAW;
+    }
+    let moves = board.get_moves();
+    if moves.is_empty() {
+        return terminal_score(board, ply, DRAW);
+    }
+    if depth == 0 {
+        return evaluator.evaluate(board);
+    }
+    let mut best = -MATE;
+    for turn in moves {
+        make_turn(board, evaluator, turn);
+        let score = -negamax(board, depth - 1, ply + 1, -beta, -alpha, evaluator);
+        undo_turn(board, evaluator);
+        if score > best {
+            best = score;
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+    }
+    best
+}
@@ -0,0 +1,98 @@
+use crate::game::{Board, Color, GameState};
+
+use super::{Engine, SearchLimits};
+
+/// Number of plies after which a game is stopped and counted as a draw, if
+/// no limit is given
+pub const DEFAULT_MAX_PLIES: u32 = 400;
+
+/// Results of a match, from the point of view of the first engine
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchResult {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl MatchResult {
+    /// Returns the number of games played
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Returns the fraction of the points the first engine scored, with a
+    /// win worth 1 and a draw worth half
+    pub fn score(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.5;
+        }
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+    }
+
+    /// Record a game, given its final state and the color the first engine
+    /// played
+    fn record(&mut self, state: &GameState, first: Color) {
+        match state {
+            GameState::Win(color, _) if *color == first => self.wins += 1,
+            GameState::Win(_, _) => self.losses += 1,
+            GameState::Draw(_) | GameState::Playing => self.draws += 1,
+        }
+    }
+}
+
+/// Play a game between two engines from the given position, with each
+/// searching within the limits on every turn.
+///
+/// Returns the state the game ended in, which is `GameState::Playing` if it
+/// was stopped after `max_plies`, or because an engine gave no turn.
+pub fn play_game(
+    white: &mut dyn Engine,
+    black: &mut dyn Engine,
+    start: &Board,
+    limits: &SearchLimits,
+    max_plies: u32,
+) -> GameState {
+    let mut board = start.clone();
+    for _ in 0..max_plies {
+        let state = board.get_game_state();
+        if state != GameState::Playing {
+            return state;
+        }
+        let result = match board.whose_turn() {
+            Color::White => white.best_move(&board, limits),
+            Color::Black => black.best_move(&board, limits),
+        };
+        let Some(result) = result else {
+            break;
+        };
+        board.make_turn(result.best);
+    }
+    board.get_game_state()
+}
+
+/// Play a match between two engines, with a game from each opening as each
+/// color, so that neither engine is favoured by the openings.
+///
+/// The starting position is used if no openings are given.
+pub fn play_match(
+    first: &mut dyn Engine,
+    second: &mut dyn Engine,
+    openings: &[Board],
+    limits: &SearchLimits,
+    max_plies: u32,
+) -> MatchResult {
+    let start = [Board::from_start()];
+    let openings = if openings.is_empty() {
+        &start[..]
+    } else {
+        openings
+    };
+    let mut result = MatchResult::default();
+    for opening in openings {
+        let state = play_game(first, second, opening, limits, max_plies);
+        result.record(&state, Color::White);
+        let state = play_game(second, first, opening, limits, max_plies);
+        result.record(&state, Color::Black);
+    }
+    result
+}
@@ -0,0 +1,144 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    eval::piece_value,
+    game::{Board, Color, Turn, PIECE_TYPES},
+};
+
+use super::{minimax::terminal_score, rng::Rng, Engine, Score, SearchLimits, SearchResult, DRAW};
+
+/// Engine playing a uniformly random legal turn, as the weakest possible
+/// opponent for testing harnesses and new engines.
+///
+/// Only the turns the limits allow are chosen from, and every other limit
+/// is ignored. The score is always 0.
+#[derive(Debug, Clone)]
+pub struct RandomMover {
+    rng: Rng,
+}
+
+impl Default for RandomMover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RandomMover {
+    pub fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    /// Create an engine whose choices are decided by the given seed
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Engine for RandomMover {
+    fn best_move(&mut self, board: &Board, limits: &SearchLimits) -> Option<SearchResult> {
+        let moves = allowed_moves(&mut board.clone(), limits);
+        if moves.is_empty() {
+            return None;
+        }
+        let best = moves[self.rng.below(moves.len())];
+        Some(SearchResult {
+            best,
+            score: Score::Centipawns(0),
+            pv: vec![best],
+            depth: 0,
+            nodes: 1,
+        })
+    }
+}
+
+/// Engine that looks one ply ahead and grabs as much material as it can,
+/// mating if it is able to, and choosing randomly between turns that are
+/// equally good.
+///
+/// It ignores what its opponent can do in reply, so makes a simple sparring
+/// partner that punishes hanging pieces. Only the turns the limits allow
+/// are chosen from, and every other limit is ignored. The score is the
+/// material balance after the turn.
+#[derive(Debug, Clone)]
+pub struct GreedyCapturer {
+    rng: Rng,
+}
+
+impl Default for GreedyCapturer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GreedyCapturer {
+    pub fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    /// Create an engine whose choices between equal turns are decided by
+    /// the given seed
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Engine for GreedyCapturer {
+    fn best_move(&mut self, board: &Board, limits: &SearchLimits) -> Option<SearchResult> {
+        let mut board = board.clone();
+        let color = board.whose_turn();
+        let mut best: Vec<Turn> = vec![];
+        let mut best_score = i32::MIN;
+        let moves = allowed_moves(&mut board, limits);
+        for &turn in &moves {
+            board.make_turn(turn);
+            let score = if board.has_legal_move() {
+                material(&board, color)
+            } else {
+                -terminal_score(&board, 1, DRAW)
+            };
+            board.undo_turn();
+            if score > best_score {
+                best_score = score;
+                best.clear();
+            }
+            if score == best_score {
+                best.push(turn);
+            }
+        }
+        if best.is_empty() {
+            return None;
+        }
+        let turn = best[self.rng.below(best.len())];
+        Some(SearchResult {
+            best: turn,
+            score: Score::from_value(best_score),
+            pv: vec![turn],
+            depth: 1,
+            nodes: moves.len() as u64,
+        })
+    }
+}
+
+/// Returns the legal turns that the limits allow
+fn allowed_moves(board: &mut Board, limits: &SearchLimits) -> Vec<Turn> {
+    let mut moves = board.get_moves();
+    moves.retain(|turn| limits.allows(turn));
+    moves
+}
+
+/// Returns the material balance in centipawns, from the point of view of
+/// the given player
+fn material(board: &Board, color: Color) -> i32 {
+    PIECE_TYPES
+        .into_iter()
+        .map(|kind| {
+            let count = board.pieces(kind, color).count() as i32
+                - board.pieces(kind, !color).count() as i32;
+            count * piece_value(kind)
+        })
+        .sum()
+}
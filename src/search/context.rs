@@ -0,0 +1,152 @@
+use alloc::{vec, vec::Vec};
+use core::cmp::Reverse;
+
+use crate::game::{Board, Turn};
+
+/// Number of killer turns remembered at each ply
+const KILLERS: usize = 2;
+
+/// Largest history score, which keeps quiet turns ordered after killers
+const HISTORY_MAX: i32 = 1 << 20;
+
+/// Tables for ordering turns that are kept from one search to the next, so
+/// that what was learned about the position isn't lost.
+///
+/// Killer turns are quiet turns that caused a cutoff at the same ply
+/// elsewhere in the tree, and the history table scores quiet turns by how
+/// often they caused cutoffs anywhere. Custom searches can share a context
+/// with the built-in ones.
+///
+/// A line expected from the next position searched can also be given with
+/// `warm_start`, so that analysis picks up where an earlier search, or
+/// another engine, left off.
+#[derive(Debug, Clone)]
+pub struct SearchContext {
+    /// Killer turns at each ply, most recent first
+    killers: Vec<[Option<Turn>; KILLERS]>,
+
+    /// Score of each quiet turn, indexed by the squares it moves from and to
+    history: Vec<[i32; 64]>,
+
+    /// Line to search first, and the hash of the position it starts from
+    warm_start: Option<(u64, Vec<Turn>)>,
+}
+
+impl Default for SearchContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchContext {
+    pub fn new() -> Self {
+        Self {
+            killers: vec![],
+            history: vec![[0; 64]; 64],
+            warm_start: None,
+        }
+    }
+
+    /// Forget all the killer turns and history scores, and any line given
+    /// to `warm_start`
+    pub fn clear(&mut self) {
+        self.killers.clear();
+        self.history.fill([0; 64]);
+        self.warm_start = None;
+    }
+
+    /// Give a line expected from the given position, such as the rest of
+    /// the line from an earlier search after the turns played since, or one
+    /// from another engine.
+    ///
+    /// The next search of this position tries the line first for as long as
+    /// it agrees with it, and its quiet turns are recorded as killers at
+    /// their plies. The line is cut short at its first illegal turn, and is
+    /// ignored if a different position is searched next.
+    pub fn warm_start(&mut self, board: &Board, pv: &[Turn]) {
+        let hash = board.zobrist_hash();
+        let mut board = board.clone();
+        let mut line = vec![];
+        for &turn in pv {
+            if !board.get_moves().contains(&turn) {
+                break;
+            }
+            board.make_turn(turn);
+            line.push(turn);
+        }
+        for (ply, &turn) in line.iter().enumerate() {
+            if is_quiet(&turn) {
+                self.add_killer(turn, ply);
+            }
+        }
+        self.warm_start = Some((hash, line));
+    }
+
+    /// Take the line given to `warm_start`, if it was given for the given
+    /// position
+    pub(super) fn take_warm_start(&mut self, board: &Board) -> Vec<Turn> {
+        match self.warm_start.take() {
+            Some((hash, line)) if hash == board.zobrist_hash() => line,
+            _ => vec![],
+        }
+    }
+
+    /// Returns the killer turns at the given ply, most recent first
+    pub fn killers(&self, ply: usize) -> [Option<Turn>; KILLERS] {
+        self.killers.get(ply).copied().unwrap_or_default()
+    }
+
+    /// Returns the history score of the given turn
+    pub fn history(&self, turn: &Turn) -> i32 {
+        self.history[turn.from.pos()][turn.to.pos()]
+    }
+
+    /// Record that the given turn caused a cutoff at the given ply and
+    /// depth. Only quiet turns are recorded, as captures are already
+    /// searched early.
+    pub fn record_cutoff(&mut self, turn: Turn, ply: usize, depth: u32) {
+        if !is_quiet(&turn) {
+            return;
+        }
+        self.add_killer(turn, ply);
+        let score = &mut self.history[turn.from.pos()][turn.to.pos()];
+        *score = (*score + (depth * depth) as i32).min(HISTORY_MAX);
+    }
+
+    /// Make the given turn the most recent killer at the given ply
+    fn add_killer(&mut self, turn: Turn, ply: usize) {
+        if self.killers.len() <= ply {
+            self.killers.resize(ply + 1, [None; KILLERS]);
+        }
+        let killers = &mut self.killers[ply];
+        if killers[0] != Some(turn) {
+            killers.rotate_right(1);
+            killers[0] = Some(turn);
+        }
+    }
+
+    /// Sort the given turns so that the most promising are searched first:
+    /// captures of the most valuable pieces by the least valuable, then
+    /// promotions, then killer turns, then other quiet turns by their
+    /// history scores
+    pub fn order_moves(&self, board: &Board, moves: &mut [Turn], ply: usize) {
+        let killers = self.killers(ply);
+        moves.sort_by_cached_key(|turn| {
+            Reverse(if let Some(capture) = turn.capture {
+                let victim = board.at_position(capture).map_or(0, |p| p.kind.value());
+                4 * HISTORY_MAX + victim as i32 * 16 - turn.kind.value() as i32
+            } else if let Some(promote_to) = turn.promote_to {
+                3 * HISTORY_MAX + promote_to.value() as i32
+            } else if let Some(i) = killers.iter().position(|k| *k == Some(*turn)) {
+                2 * HISTORY_MAX - i as i32
+            } else {
+                self.history(turn)
+            })
+        });
+    }
+}
+
+/// Returns whether a turn neither captures nor promotes
+fn is_quiet(turn: &Turn) -> bool {
+    turn.capture.is_none() && turn.promote_to.is_none()
+}
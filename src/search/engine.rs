@@ -0,0 +1,60 @@
+use crate::{
+    eval::{Classical, Evaluator},
+    game::{Board, Turn},
+};
+
+use super::{iterative_deepening_with, SearchContext, SearchLimits, SearchOptions, SearchResult};
+
+/// Something that can choose a turn to make, so that match runners, UCI
+/// frontends and GUIs can host the built-in searches, simple players, or
+/// engines written outside the crate
+pub trait Engine {
+    /// Find the best turn for the player to move within the limits, along
+    /// with its score and expected line, or `None` if there are no legal
+    /// turns
+    fn best_move(&mut self, board: &Board, limits: &SearchLimits) -> Option<SearchResult>;
+}
+
+/// Engine using the iterative deepening alpha-beta search, which keeps its
+/// `SearchContext` from one turn to the next
+#[derive(Debug, Clone, Default)]
+pub struct AlphaBeta<E: Evaluator = Classical> {
+    pub evaluator: E,
+    pub options: SearchOptions,
+    pub context: SearchContext,
+}
+
+impl AlphaBeta {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<E: Evaluator> AlphaBeta<E> {
+    /// Create an engine scoring positions with the given evaluator
+    pub fn with_evaluator(evaluator: E) -> Self {
+        Self {
+            evaluator,
+            options: SearchOptions::default(),
+            context: SearchContext::new(),
+        }
+    }
+
+    /// Give a line expected from the given position, which the next search
+    /// of it tries first, as described in `SearchContext::warm_start`
+    pub fn warm_start(&mut self, board: &Board, pv: &[Turn]) {
+        self.context.warm_start(board, pv);
+    }
+}
+
+impl<E: Evaluator> Engine for AlphaBeta<E> {
+    fn best_move(&mut self, board: &Board, limits: &SearchLimits) -> Option<SearchResult> {
+        iterative_deepening_with(
+            &mut board.clone(),
+            limits,
+            &self.options,
+            &mut self.context,
+            &mut self.evaluator,
+        )
+    }
+}
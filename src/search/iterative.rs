@@ -0,0 +1,468 @@
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::{
+    eval::{Classical, Evaluator},
+    game::{Board, Color, PieceType, Turn},
+};
+
+#[cfg(feature = "std")]
+use super::TimeManager;
+use super::{
+    minimax::{is_search_draw, terminal_score},
+    MoveAnalysis, Score, SearchContext, SearchLimits, SearchOptions, SearchResult, DRAW, MATE,
+    MAX_DEPTH,
+};
+
+/// How often to check the clock, in nodes
+#[cfg(feature = "std")]
+const CLOCK_INTERVAL: u64 = 1024;
+
+/// Find the best turn for the player to move, along with its score and
+/// expected line, by searching to depth 1, 2, 3 and so on until the limits are reached.
+///
+/// The result of the last iteration to finish is given, and each iteration
+/// searches the best line of the one before it first, which makes pruning
+/// more effective. The first iteration always finishes, so a turn is found
+/// however tight the limits are. Returns `None` if there are no legal turns,
+/// or none that the limits allow.
+pub fn iterative_deepening(board: &mut Board, limits: &SearchLimits) -> Option<SearchResult> {
+    iterative_deepening_with(
+        board,
+        limits,
+        &SearchOptions::default(),
+        &mut SearchContext::new(),
+        &mut Classical::new(),
+    )
+}
+
+/// Find the best turn for the player to move, like `iterative_deepening`,
+/// but with the given options, scoring positions with the given evaluator,
+/// and ordering turns using the given context, which is updated as the
+/// search goes
+pub fn iterative_deepening_with<E: Evaluator>(
+    board: &mut Board,
+    limits: &SearchLimits,
+    options: &SearchOptions,
+    context: &mut SearchContext,
+    evaluator: &mut E,
+) -> Option<SearchResult> {
+    let mut search = Search::new(board, limits, options, context, evaluator);
+    let mut best: Option<SearchResult> = None;
+    for depth in 1..=limits.max_depth() {
+        let guess = best.as_ref().map(|result| result.score.value());
+        let Some((turn, score)) = search.iteration(depth, guess) else {
+            break;
+        };
+        best = Some(SearchResult {
+            best: turn,
+            score: Score::from_value(score),
+            pv: search.pv.first().cloned().unwrap_or_default(),
+            depth,
+            nodes: search.nodes,
+        });
+        // A forced mate won't be improved on by searching deeper
+        if score.abs() >= MATE - depth as i32 {
+            break;
+        }
+        #[cfg(feature = "std")]
+        if let Some(time_manager) = &mut search.time_manager {
+            if time_manager.should_stop(turn, search.start.elapsed()) {
+                break;
+            }
+        }
+    }
+    // Count the nodes of an unfinished iteration too
+    best.map(|result| SearchResult {
+        nodes: search.nodes,
+        ..result
+    })
+}
+
+/// Score every turn from the root that the limits allow, along with the
+/// line expected to follow it, best first, by searching each of them with
+/// a full window at depth 1, 2, 3 and so on until the limits are reached.
+///
+/// This is slower than finding just the best turn, as a turn can't be cut
+/// short once it is known to be worse than another, so is meant for
+/// showing candidate turns and checking for blunders. The results of the
+/// last iteration to finish are given, and are empty if there are no legal
+/// turns.
+pub fn analyze_all(board: &mut Board, limits: &SearchLimits) -> Vec<MoveAnalysis> {
+    let options = SearchOptions::default();
+    let mut context = SearchContext::new();
+    let mut evaluator = Classical::new();
+    let mut search = Search::new(board, limits, &options, &mut context, &mut evaluator);
+    let mut analysis = vec![];
+    for depth in 1..=limits.max_depth() {
+        let Some(result) = search.analyze_root(depth, &analysis) else {
+            break;
+        };
+        analysis = result;
+        let Some(best) = analysis.first() else {
+            break;
+        };
+        search.prev_pv = best.pv.clone();
+        search.can_stop = true;
+        #[cfg(feature = "std")]
+        if let Some(time_manager) = &mut search.time_manager {
+            if time_manager.should_stop(best.turn, search.start.elapsed()) {
+                break;
+            }
+        }
+    }
+    analysis
+}
+
+/// State of an iterative deepening search
+struct Search<'a, E> {
+    board: &'a mut Board,
+    limits: &'a SearchLimits,
+    options: &'a SearchOptions,
+    context: &'a mut SearchContext,
+    evaluator: &'a mut E,
+
+    /// Number of positions searched so far
+    nodes: u64,
+    node_limit: Option<u64>,
+    #[cfg(feature = "std")]
+    start: Instant,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+
+    /// Decides whether to start another iteration, if the player to move
+    /// has a clock
+    #[cfg(feature = "std")]
+    time_manager: Option<TimeManager>,
+
+    /// Whether the limits have been reached, so the current iteration
+    /// should be abandoned
+    stopped: bool,
+
+    /// Whether the limits may stop the search, which they can't until an
+    /// iteration has finished
+    can_stop: bool,
+
+    /// Best line found from each ply of the current iteration
+    pv: Vec<Vec<Turn>>,
+
+    /// Best line found by the previous iteration, which is searched first
+    prev_pv: Vec<Turn>,
+
+    /// Line given to `SearchContext::warm_start`, which is searched first
+    /// for as long as the search agrees with it
+    warm_start: Vec<Turn>,
+}
+
+impl<'a, E: Evaluator> Search<'a, E> {
+    fn new(
+        board: &'a mut Board,
+        limits: &'a SearchLimits,
+        options: &'a SearchOptions,
+        context: &'a mut SearchContext,
+        evaluator: &'a mut E,
+    ) -> Self {
+        evaluator.reset(board);
+        #[cfg(feature = "std")]
+        let start = Instant::now();
+        #[cfg(feature = "std")]
+        let color = board.whose_turn();
+        let warm_start = context.take_warm_start(board);
+        Self {
+            board,
+            limits,
+            options,
+            context,
+            evaluator,
+            nodes: 0,
+            node_limit: limits.node_limit(),
+            #[cfg(feature = "std")]
+            start,
+            #[cfg(feature = "std")]
+            deadline: limits.hard_time(color).map(|time| start + time),
+            #[cfg(feature = "std")]
+            time_manager: limits.time_manager(color),
+            stopped: false,
+            can_stop: false,
+            pv: vec![],
+            prev_pv: warm_start.clone(),
+            warm_start,
+        }
+    }
+
+    /// Returns whether the limits have been reached, only checking the clock
+    /// every so often, as it is slow
+    fn out_of_budget(&self) -> bool {
+        if self.node_limit.is_some_and(|limit| self.nodes >= limit) {
+            return true;
+        }
+        #[cfg(feature = "std")]
+        if self.nodes.is_multiple_of(CLOCK_INTERVAL)
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return true;
+        }
+        false
+    }
+
+    /// Run one iteration to the given depth, returning the best turn and
+    /// its score, or `None` if the limits were reached first.
+    ///
+    /// If aspiration windows are enabled, the score of the last iteration is
+    /// used to narrow the window, which is widened until the score is inside
+    /// it.
+    fn iteration(&mut self, depth: u32, guess: Option<i32>) -> Option<(Turn, i32)> {
+        let guess =
+            guess.filter(|guess| self.options.aspiration && guess.abs() < MATE - MAX_DEPTH as i32);
+        let result = match guess {
+            None => self.root(depth, -MATE, MATE)?,
+            Some(guess) => {
+                let mut delta = self.options.aspiration_window.max(1);
+                loop {
+                    let alpha = (guess - delta).max(-MATE);
+                    let beta = (guess + delta).min(MATE);
+                    let result = self.root(depth, alpha, beta)?;
+                    if (result.1 > alpha || alpha == -MATE) && (result.1 < beta || beta == MATE) {
+                        break result;
+                    }
+                    delta = delta.saturating_mul(2);
+                }
+            }
+        };
+        let pv = self.pv.first().cloned().unwrap_or_default();
+        // Keep following a line given to warm start the search past the end
+        // of the one found, while they agree
+        self.prev_pv = if self.warm_start.starts_with(&pv) {
+            self.warm_start.clone()
+        } else {
+            pv
+        };
+        self.can_stop = true;
+        Some(result)
+    }
+
+    /// Search every turn from the root to the given depth, with the given
+    /// window, returning the best turn and its score
+    fn root(&mut self, depth: u32, mut alpha: i32, beta: i32) -> Option<(Turn, i32)> {
+        let mut moves = self.board.get_moves();
+        moves.retain(|turn| self.limits.allows(turn));
+        self.context.order_moves(self.board, &mut moves, 0);
+        if let Some(first) = self.prev_pv.first() {
+            order_first(&mut moves, first);
+        }
+        let mut best: Option<(Turn, i32)> = None;
+        for (i, turn) in moves.into_iter().enumerate() {
+            self.make_turn(turn);
+            let score = -self.negamax(depth - 1, 1, -beta, -alpha, i == 0, true);
+            self.undo_turn();
+            if self.stopped {
+                return None;
+            }
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((turn, score));
+                alpha = alpha.max(score);
+                self.update_pv(0, turn);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    /// Search every turn from the root to the given depth with a full
+    /// window, returning each with its score and line, best first, or
+    /// `None` if the limits were reached first. Turns are searched in the
+    /// order of the previous iteration's results.
+    fn analyze_root(&mut self, depth: u32, previous: &[MoveAnalysis]) -> Option<Vec<MoveAnalysis>> {
+        let mut moves = self.board.get_moves();
+        moves.retain(|turn| self.limits.allows(turn));
+        self.context.order_moves(self.board, &mut moves, 0);
+        for analysis in previous.iter().rev() {
+            order_first(&mut moves, &analysis.turn);
+        }
+        let mut analysis = vec![];
+        for (i, turn) in moves.into_iter().enumerate() {
+            self.make_turn(turn);
+            let score = -self.negamax(depth - 1, 1, -MATE, MATE, i == 0, true);
+            self.undo_turn();
+            if self.stopped {
+                return None;
+            }
+            let mut pv = vec![turn];
+            pv.extend_from_slice(&self.pv[1]);
+            analysis.push(MoveAnalysis {
+                turn,
+                score: Score::from_value(score),
+                pv,
+            });
+        }
+        // Stable, so equal turns keep their order
+        analysis.sort_by_key(|analysis| core::cmp::Reverse(analysis.score));
+        Some(analysis)
+    }
+
+    /// Fail-soft negamax, where `ply` is the distance from the root, `on_pv`
+    /// is whether every turn so far follows the previous best line, and
+    /// `allow_null` is whether null move pruning may be tried
+    fn negamax(
+        &mut self,
+        depth: u32,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        on_pv: bool,
+        allow_null: bool,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.can_stop && self.out_of_budget() {
+            self.stopped = true;
+        }
+        if self.stopped {
+            return 0;
+        }
+        if self.pv.len() <= ply {
+            self.pv.resize(ply + 1, vec![]);
+        }
+        self.pv[ply].clear();
+
+        if is_search_draw(self.board) {
+            return self.draw_score(ply);
+        }
+        let mut moves = self.board.get_moves();
+        if moves.is_empty() {
+            return terminal_score(self.board, ply as i32, self.draw_score(ply));
+        }
+        if depth == 0 {
+            return self.evaluator.evaluate(self.board);
+        }
+        if allow_null && !on_pv {
+            if let Some(score) = self.null_move(depth, ply, beta) {
+                return score;
+            }
+        }
+
+        self.context.order_moves(self.board, &mut moves, ply);
+        let pv_turn = self.prev_pv.get(ply).filter(|_| on_pv).copied();
+        if let Some(pv_turn) = &pv_turn {
+            order_first(&mut moves, pv_turn);
+        }
+
+        let mut best = -MATE;
+        for turn in moves {
+            self.make_turn(turn);
+            let child_on_pv = pv_turn == Some(turn);
+            let score = -self.negamax(depth - 1, ply + 1, -beta, -alpha, child_on_pv, true);
+            self.undo_turn();
+            if self.stopped {
+                return 0;
+            }
+            if score > best {
+                best = score;
+                if score > alpha {
+                    alpha = score;
+                    self.update_pv(ply, turn);
+                }
+                if alpha >= beta {
+                    self.context.record_cutoff(turn, ply, depth);
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    /// Try passing the turn, returning a score at least `beta` if the
+    /// opponent still can't bring the score below it, as the position is
+    /// then too good to need searching properly.
+    ///
+    /// This isn't tried in check, where passing is illegal, or when the
+    /// player to move only has pawns, where zugzwang is common. A cutoff is
+    /// also checked with a shallower search without passing, in case of
+    /// zugzwang elsewhere.
+    fn null_move(&mut self, depth: u32, ply: usize, beta: i32) -> Option<i32> {
+        let reduction = self.options.null_move_reduction;
+        if !self.options.null_move
+            || depth <= reduction
+            || beta.abs() >= MATE - MAX_DEPTH as i32
+            || self.board.is_check()
+            || !has_pieces(self.board, self.board.whose_turn())
+            || self.evaluator.evaluate(self.board) < beta
+        {
+            return None;
+        }
+        self.board.make_null_move();
+        let score = -self.negamax(
+            depth - 1 - reduction,
+            ply + 1,
+            -beta,
+            1 - beta,
+            false,
+            false,
+        );
+        self.board.undo_null_move();
+        if self.stopped || score < beta {
+            return None;
+        }
+        let verified = self.negamax(depth - reduction, ply, beta - 1, beta, false, false);
+        // Passing can't prove a mate, so don't claim one
+        (!self.stopped && verified >= beta).then_some(score.min(MATE - MAX_DEPTH as i32 - 1))
+    }
+
+    /// Make a turn, passing it on to the evaluator first
+    fn make_turn(&mut self, turn: Turn) {
+        self.evaluator.make_turn(self.board, &turn);
+        self.board.make_turn(turn);
+    }
+
+    /// Undo the last turn, passing it on to the evaluator after
+    fn undo_turn(&mut self) {
+        self.board.undo_turn();
+        self.evaluator.undo_turn();
+    }
+
+    /// Returns the score of a draw for the player to move at the given ply,
+    /// which is worse for the player to move at the root by the contempt
+    fn draw_score(&self, ply: usize) -> i32 {
+        if ply.is_multiple_of(2) {
+            DRAW - self.options.contempt
+        } else {
+            DRAW + self.options.contempt
+        }
+    }
+
+    /// Make the best line from `ply` the given turn, followed by the best
+    /// line from the position after it
+    fn update_pv(&mut self, ply: usize, turn: Turn) {
+        let (line, rest) = self.pv.split_at_mut(ply + 1);
+        let line = &mut line[ply];
+        line.clear();
+        line.push(turn);
+        if let Some(rest) = rest.first() {
+            line.extend_from_slice(rest);
+        }
+    }
+}
+
+/// Returns whether the given player has any pieces other than pawns and
+/// their king
+fn has_pieces(board: &Board, color: Color) -> bool {
+    [
+        PieceType::Queen,
+        PieceType::Rook,
+        PieceType::Bishop,
+        PieceType::Knight,
+    ]
+    .into_iter()
+    .any(|kind| !board.pieces(kind, color).is_empty())
+}
+
+/// Move the given turn to the front of the list, if it is in it
+fn order_first(moves: &mut [Turn], first: &Turn) {
+    if let Some(i) = moves.iter().position(|turn| turn == first) {
+        moves[..=i].rotate_right(1);
+    }
+}
@@ -0,0 +1,94 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::game::{Color, Turn};
+
+use super::{Clock, TimeManager};
+
+/// Deepest iteration any search goes to
+pub const MAX_DEPTH: u32 = 64;
+
+/// Limits on how long a search may run for, and which turns it may choose,
+/// mirroring the parameters of the UCI `go` command.
+///
+/// With no limits given, the search runs to `MAX_DEPTH`. Times need a clock,
+/// so are ignored without the `std` feature.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Deepest iteration to search
+    pub depth: Option<u32>,
+
+    /// Number of nodes to stop searching after
+    pub nodes: Option<u64>,
+
+    /// Exact time to search for
+    pub move_time: Option<Duration>,
+
+    /// White's clock, for deciding how long to search when it is White's
+    /// turn
+    pub white: Option<Clock>,
+
+    /// Black's clock, for deciding how long to search when it is Black's
+    /// turn
+    pub black: Option<Clock>,
+
+    /// Ignore every other limit, and search to `MAX_DEPTH`
+    pub infinite: bool,
+
+    /// Only look for a mate in this many moves, stopping once one is found
+    pub mate: Option<u32>,
+
+    /// Only choose from these turns at the root, if any are given
+    pub search_moves: Vec<Turn>,
+}
+
+impl SearchLimits {
+    /// Returns the clock of the given player
+    pub fn clock(&self, color: Color) -> Option<&Clock> {
+        match color {
+            Color::White => self.white.as_ref(),
+            Color::Black => self.black.as_ref(),
+        }
+    }
+
+    /// Returns the deepest iteration to search
+    pub fn max_depth(&self) -> u32 {
+        if self.infinite {
+            return MAX_DEPTH;
+        }
+        let mate_depth = self.mate.map(|moves| (moves * 2).saturating_sub(1));
+        [self.depth, mate_depth]
+            .into_iter()
+            .flatten()
+            .fold(MAX_DEPTH, u32::min)
+            .max(1)
+    }
+
+    /// Returns the number of nodes to stop searching after
+    pub fn node_limit(&self) -> Option<u64> {
+        self.nodes.filter(|_| !self.infinite)
+    }
+
+    /// Returns a time manager for the given player's clock, if they have one
+    pub fn time_manager(&self, color: Color) -> Option<TimeManager> {
+        if self.infinite {
+            return None;
+        }
+        self.clock(color).map(TimeManager::new)
+    }
+
+    /// Returns the time after which the search must stop, for the given
+    /// player to move
+    pub fn hard_time(&self, color: Color) -> Option<Duration> {
+        if self.infinite {
+            return None;
+        }
+        let clock = self.time_manager(color).map(|tm| tm.hard_limit());
+        [self.move_time, clock].into_iter().flatten().min()
+    }
+
+    /// Returns whether the given turn may be chosen at the root
+    pub fn allows(&self, turn: &Turn) -> bool {
+        self.search_moves.is_empty() || self.search_moves.contains(turn)
+    }
+}
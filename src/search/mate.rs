@@ -0,0 +1,62 @@
+use alloc::{vec, vec::Vec};
+
+use crate::game::{Board, Turn};
+
+/// Find the quickest forced mate for the player to move in at most the
+/// given number of moves.
+///
+/// Returns the mating line, with the defender making the replies that put
+/// the mate off longest, or `None` if there is no forced mate that quick.
+/// Unlike the scores from the other searches, this is exact, so is suited to
+/// checking puzzles and compositions, but it searches every line so is only
+/// practical for short mates.
+pub fn find_mate(board: &mut Board, moves: u32) -> Option<Vec<Turn>> {
+    (1..=moves).find_map(|moves| mate_in(board, moves))
+}
+
+/// Find a turn for the player to move that forces mate in at most the
+/// given number of moves, returning the mating line
+fn mate_in(board: &mut Board, moves: u32) -> Option<Vec<Turn>> {
+    let mut turns = board.get_moves();
+    // Checks are the most likely to lead to mate, so try them first
+    turns.sort_by_cached_key(|turn| {
+        board.make_turn(*turn);
+        let check = board.is_check();
+        board.undo_turn();
+        !check
+    });
+    turns.into_iter().find_map(|turn| {
+        board.make_turn(turn);
+        let line = defend(board, moves);
+        board.undo_turn();
+        line.map(|rest| [vec![turn], rest].concat())
+    })
+}
+
+/// Returns the line from a position where the defender is to move, and
+/// every reply allows mate within `moves - 1` more moves, with the reply
+/// that puts it off longest, or `None` if the defender can avoid mate
+fn defend(board: &mut Board, moves: u32) -> Option<Vec<Turn>> {
+    let replies = board.get_moves();
+    if replies.is_empty() {
+        // Either mate, or a draw
+        return (board.is_check() && !board.has_legal_move()).then(Vec::new);
+    }
+    if moves <= 1 {
+        return None;
+    }
+    let mut longest: Option<Vec<Turn>> = None;
+    for reply in replies {
+        board.make_turn(reply);
+        let line = find_mate(board, moves - 1);
+        board.undo_turn();
+        let line = [vec![reply], line?].concat();
+        if longest
+            .as_ref()
+            .is_none_or(|longest| line.len() > longest.len())
+        {
+            longest = Some(line);
+        }
+    }
+    longest
+}
@@ -0,0 +1,302 @@
+use alloc::{vec, vec::Vec};
+use std::time::Instant;
+
+use crate::{
+    eval::{Classical, Evaluator},
+    game::{Board, Turn},
+};
+
+use super::{
+    minimax::{make_turn, undo_turn},
+    rng::Rng,
+    Engine, Score, SearchLimits, SearchResult, DRAW, MATE,
+};
+
+/// Centipawn score at which a position is treated as about 3 to 1 in
+/// favour of the player to move, for converting scores to results
+const SCORE_SCALE: f64 = 400.0;
+
+/// Largest centipawn score given to a turn that doesn't end the game,
+/// however well its playouts went, as they can't prove a mate
+const MAX_SCORE: i32 = 10_000;
+
+/// Number of visits to make if the limits have no node or time limit
+const DEFAULT_VISITS: u64 = 10_000;
+
+/// How positions reached by the tree are given a result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rollout {
+    /// Play random turns until the game ends or the given number of plies
+    /// have been played, then score the position with the evaluator
+    Random(u32),
+
+    /// Score the position with the evaluator straight away
+    Evaluator,
+}
+
+/// Engine using Monte Carlo tree search, which grows a tree of the most
+/// promising lines using the UCT rule, rather than searching every line to
+/// a fixed depth.
+///
+/// Each node of the limits is one visit to the tree, and the depth and mate
+/// limits are ignored. The soft limit of the time manager is used for the
+/// player's clock. Without a node or time limit, 10000 visits are made, or
+/// visits continue indefinitely if the search is infinite. This needs the
+/// `std` feature.
+#[derive(Debug, Clone)]
+pub struct Mcts<E: Evaluator = Classical> {
+    pub evaluator: E,
+    pub rollout: Rollout,
+
+    /// How strongly less visited turns are favoured over ones that have
+    /// done well so far
+    pub exploration: f64,
+
+    rng: Rng,
+}
+
+impl Default for Mcts {
+    fn default() -> Self {
+        Self::with_evaluator(Classical::new())
+    }
+}
+
+impl Mcts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<E: Evaluator> Mcts<E> {
+    /// Create an engine scoring positions with the given evaluator
+    pub fn with_evaluator(evaluator: E) -> Self {
+        Self {
+            evaluator,
+            rollout: Rollout::Evaluator,
+            exploration: core::f64::consts::SQRT_2,
+            rng: Rng::new(0),
+        }
+    }
+
+    /// Seed the random number generator used for random rollouts
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    /// Returns the expected result of a position for the player to move,
+    /// from 0 for a loss to 1 for a win
+    fn rollout(&mut self, board: &mut Board) -> f64 {
+        let Rollout::Random(max_plies) = self.rollout else {
+            return score_to_result(self.evaluator.evaluate(board));
+        };
+        let mut plies = 0;
+        let result = loop {
+            let moves = board.get_moves();
+            if moves.is_empty() {
+                break game_result(board);
+            }
+            if plies == max_plies {
+                break score_to_result(self.evaluator.evaluate(board));
+            }
+            make_turn(
+                board,
+                &mut self.evaluator,
+                moves[self.rng.below(moves.len())],
+            );
+            plies += 1;
+        };
+        for _ in 0..plies {
+            undo_turn(board, &mut self.evaluator);
+        }
+        // Flip the result back to the player to move at the start
+        if plies % 2 == 0 {
+            result
+        } else {
+            1.0 - result
+        }
+    }
+
+    /// Walk the tree from the root to a leaf, adding a node for one turn not
+    /// yet in the tree, then score the leaf and pass the result back up
+    fn visit(&mut self, tree: &mut Vec<Node>, board: &mut Board) {
+        let mut path = vec![0];
+        let mut node = 0;
+        // Select
+        while tree[node].unexpanded.is_empty() && !tree[node].children.is_empty() {
+            let parent_visits = tree[node].visits as f64;
+            node = *tree[node]
+                .children
+                .iter()
+                .max_by(|&&a, &&b| {
+                    let a = tree[a].uct(parent_visits, self.exploration);
+                    let b = tree[b].uct(parent_visits, self.exploration);
+                    a.total_cmp(&b)
+                })
+                .unwrap();
+            make_turn(board, &mut self.evaluator, tree[node].turn.unwrap());
+            path.push(node);
+        }
+        // Expand
+        if let Some(turn) = tree[node].unexpanded.pop() {
+            make_turn(board, &mut self.evaluator, turn);
+            let child = tree.len();
+            tree.push(Node::new(Some(turn), board));
+            tree[node].children.push(child);
+            node = child;
+            path.push(node);
+        }
+        // Simulate, giving the result for the player who made the last turn
+        let result = 1.0
+            - match tree[node].result {
+                Some(result) => result,
+                None => self.rollout(board),
+            };
+        // Back up, flipping the result at each ply
+        let mut result = result;
+        for &node in path.iter().rev() {
+            tree[node].visits += 1;
+            tree[node].total += result;
+            result = 1.0 - result;
+        }
+        for _ in 1..path.len() {
+            undo_turn(board, &mut self.evaluator);
+        }
+    }
+}
+
+impl<E: Evaluator> Engine for Mcts<E> {
+    fn best_move(&mut self, board: &Board, limits: &SearchLimits) -> Option<SearchResult> {
+        let mut board = board.clone();
+        self.evaluator.reset(&board);
+        let mut tree = vec![Node::new(None, &mut board)];
+        tree[0].unexpanded.retain(|turn| limits.allows(turn));
+        if tree[0].unexpanded.is_empty() {
+            return None;
+        }
+        let color = board.whose_turn();
+        let clock = limits.time_manager(color).map(|tm| tm.soft_limit());
+        let time = [limits.move_time, clock]
+            .into_iter()
+            .flatten()
+            .min()
+            .filter(|_| !limits.infinite);
+        let deadline = time.map(|time| Instant::now() + time);
+        let nodes = match (limits.node_limit(), deadline) {
+            (None, None) if !limits.infinite => Some(DEFAULT_VISITS),
+            (nodes, _) => nodes,
+        };
+        let mut visits = 0;
+        loop {
+            self.visit(&mut tree, &mut board);
+            visits += 1;
+            // Always give each turn from the root a visit
+            if !tree[0].unexpanded.is_empty() {
+                continue;
+            }
+            if nodes.is_some_and(|nodes| visits >= nodes)
+                || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                break;
+            }
+        }
+        // The most visited turn is the most reliable, and following the most
+        // visited turns from it gives the expected line
+        let mut line = vec![];
+        let mut node = 0;
+        while let Some(&child) = tree[node]
+            .children
+            .iter()
+            .max_by_key(|&&child| tree[child].visits)
+        {
+            line.push(child);
+            node = child;
+        }
+        let best = &tree[*line.first()?];
+        // Only a turn that ends the game has a certain result, which is for
+        // the opponent, who is to move after it
+        let score = match best.result {
+            Some(0.0) => MATE - 1,
+            Some(_) => DRAW,
+            None => result_to_score(best.total / best.visits as f64),
+        };
+        Some(SearchResult {
+            best: best.turn.unwrap(),
+            score: Score::from_value(score),
+            pv: line.iter().map(|&node| tree[node].turn.unwrap()).collect(),
+            depth: line.len() as u32,
+            nodes: visits,
+        })
+    }
+}
+
+/// A position in the search tree
+#[derive(Debug, Clone)]
+struct Node {
+    /// The turn leading to this position, or `None` for the root
+    turn: Option<Turn>,
+
+    /// Nodes for turns from this position that are in the tree
+    children: Vec<usize>,
+
+    /// Turns from this position that aren't in the tree yet
+    unexpanded: Vec<Turn>,
+
+    visits: u32,
+
+    /// Sum of the results of every visit, for the player who made `turn`
+    total: f64,
+
+    /// Result for the player to move, if the game is over
+    result: Option<f64>,
+}
+
+impl Node {
+    fn new(turn: Option<Turn>, board: &mut Board) -> Self {
+        let unexpanded = board.get_moves();
+        let result = unexpanded.is_empty().then(|| game_result(board));
+        Self {
+            turn,
+            children: vec![],
+            unexpanded,
+            visits: 0,
+            total: 0.0,
+            result,
+        }
+    }
+
+    /// Returns the UCT value of choosing this node, which balances how well
+    /// it has done against how rarely it has been tried
+    fn uct(&self, parent_visits: f64, exploration: f64) -> f64 {
+        let visits = self.visits as f64;
+        self.total / visits + exploration * (parent_visits.ln() / visits).sqrt()
+    }
+}
+
+/// Returns the result for the player to move of a position with no legal
+/// turns, from 0 for a loss to 1 for a win
+fn game_result(board: &Board) -> f64 {
+    // There may still be legal turns, if the game was drawn by the rules
+    if board.is_check() && !board.has_legal_move() {
+        0.0
+    } else {
+        0.5
+    }
+}
+
+/// Convert a centipawn score to an expected result, from 0 to 1
+fn score_to_result(score: i32) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-score as f64 / SCORE_SCALE))
+}
+
+/// Convert an expected result, from 0 to 1, back to a centipawn score, up
+/// to `MAX_SCORE` either way
+fn result_to_score(result: f64) -> i32 {
+    if result >= 1.0 {
+        MAX_SCORE
+    } else if result <= 0.0 {
+        -MAX_SCORE
+    } else {
+        let score = (SCORE_SCALE * (result / (1.0 - result)).log10()).round() as i32;
+        score.clamp(-MAX_SCORE, MAX_SCORE)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{
+    eval::Evaluator,
+    game::{Board, Turn},
+};
+
+use super::{DRAW, MATE};
+
+/// Find the best turn for the player to move by searching every line to the
+/// given depth, along with its score.
+///
+/// This is a plain negamax search, without any pruning, so it is only
+/// practical at low depths, but is simple enough to check other searches
+/// against. Positions at the given depth are scored by the evaluator, and
+/// repetitions, the 50 move rule and insufficient material are draws.
+/// Returns `None` if there are no legal turns.
+pub fn minimax<E: Evaluator>(
+    board: &mut Board,
+    depth: u32,
+    evaluator: &mut E,
+) -> Option<(Turn, i32)> {
+    evaluator.reset(board);
+    let mut best: Option<(Turn, i32)> = None;
+    for turn in board.get_moves() {
+        make_turn(board, evaluator, turn);
+        let score = -negamax(board, depth.saturating_sub(1), 1, evaluator);
+        undo_turn(board, evaluator);
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((turn, score));
+        }
+    }
+    best
+}
+
+/// Returns the score of the position after searching to the given depth,
+/// where `ply` is the distance from the root
+fn negamax<E: Evaluator>(board: &mut Board, depth: u32, ply: i32, evaluator: &mut E) -> i32 {
+    if is_search_draw(board) {
+        return DRAW;
+    }
+    let moves = board.get_moves();
+    if moves.is_empty() {
+        return terminal_score(board, ply, DRAW);
+    }
+    if depth == 0 {
+        return evaluator.evaluate(board);
+    }
+    moves
+        .into_iter()
+        .map(|turn| {
+            make_turn(board, evaluator, turn);
+            let score = -negamax(board, depth - 1, ply + 1, evaluator);
+            undo_turn(board, evaluator);
+            score
+        })
+        .max()
+        .unwrap()
+}
+
+/// Make a turn, passing it on to the evaluator first
+pub(super) fn make_turn<E: Evaluator>(board: &mut Board, evaluator: &mut E, turn: Turn) {
+    evaluator.make_turn(board, &turn);
+    board.make_turn(turn);
+}
+
+/// Undo the last turn, passing it on to the evaluator after
+pub(super) fn undo_turn<E: Evaluator>(board: &mut Board, evaluator: &mut E) {
+    board.undo_turn();
+    evaluator.undo_turn();
+}
+
+/// Returns whether a position inside a search tree is a draw, because it
+/// repeats an earlier position, the 50 move rule has been reached without
+/// a checkmate, or neither player can ever checkmate
+pub(super) fn is_search_draw(board: &Board) -> bool {
+    board.is_repetition()
+        || board.is_insufficient_material()
+        || (board.is_50_move_rule() && (!board.is_check() || board.has_legal_move()))
+}
+
+/// Returns the score of a position with no legal turns, which is either
+/// checkmate or scores `draw`
+pub(super) fn terminal_score(board: &Board, ply: i32, draw: i32) -> i32 {
+    // There may still be legal turns, if the game was drawn by the rules
+    if board.is_check() && !board.has_legal_move() {
+        -(MATE - ply)
+    } else {
+        draw
+    }
+}
@@ -0,0 +1,51 @@
+//! Searching for the best turn in a position.
+//!
+//! Scores are given in centipawns, from the point of view of the player
+//! whose turn it is, so a positive score is good for the player to move.
+
+mod alpha_beta;
+mod arena;
+mod baseline;
+mod context;
+mod engine;
+mod iterative;
+mod limits;
+mod mate;
+#[cfg(feature = "std")]
+mod mcts;
+mod minimax;
+mod options;
+mod result;
+pub(crate) mod rng;
+mod screening;
+#[cfg(feature = "std")]
+mod spsa;
+mod tablebase;
+mod time;
+
+pub use alpha_beta::{alpha_beta, alpha_beta_window};
+pub use arena::{play_game, play_match, MatchResult, DEFAULT_MAX_PLIES};
+pub use baseline::{GreedyCapturer, RandomMover};
+pub use context::SearchContext;
+pub use engine::{AlphaBeta, Engine};
+pub use iterative::{analyze_all, iterative_deepening, iterative_deepening_with};
+pub use limits::{SearchLimits, MAX_DEPTH};
+pub use mate::find_mate;
+#[cfg(feature = "std")]
+pub use mcts::{Mcts, Rollout};
+pub use minimax::minimax;
+pub use options::SearchOptions;
+pub use result::{MoveAnalysis, Score, SearchResult};
+pub use screening::{screen_games, DepthReport, ScreeningOptions};
+#[cfg(feature = "std")]
+pub use spsa::{Spsa, SpsaParam};
+pub use tablebase::{Ending, Tablebase, TablebaseResult};
+pub use time::{Clock, TimeManager};
+
+/// Score of checkmating the opponent immediately. Mates further away score
+/// one less for each ply it takes to reach them, so that quicker mates are
+/// preferred.
+pub const MATE: i32 = 100_000;
+
+/// Score of a drawn position
+pub const DRAW: i32 = 0;
@@ -0,0 +1,38 @@
+/// Settings for the alpha-beta search, which can be tuned to trade accuracy
+/// for speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Whether to try passing the turn, and skip searching a position if
+    /// the opponent still can't catch up (null move pruning)
+    pub null_move: bool,
+
+    /// How many plies shallower the position after passing is searched
+    pub null_move_reduction: u32,
+
+    /// Whether to search each iteration with a narrow window around the
+    /// score of the one before it (aspiration windows), which prunes more,
+    /// searching again with a wider window if the score falls outside it
+    pub aspiration: bool,
+
+    /// Distance either side of the previous score the window starts at, in
+    /// centipawns. The window doubles each time the score falls outside it.
+    pub aspiration_window: i32,
+
+    /// How much worse than an equal position a draw is for the player the
+    /// search is for, in centipawns, applied to stalemates and draws by
+    /// the rules. A positive value avoids draws, and a negative one seeks
+    /// them out.
+    pub contempt: i32,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            null_move: true,
+            null_move_reduction: 2,
+            aspiration: true,
+            aspiration_window: 50,
+            contempt: 0,
+        }
+    }
+}
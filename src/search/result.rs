@@ -0,0 +1,103 @@
+use alloc::vec::Vec;
+use core::{cmp::Ordering, fmt::Display};
+
+use crate::game::Turn;
+
+use super::{MATE, MAX_DEPTH};
+
+/// Score of a position for the player to move, which is either an estimate
+/// in centipawns, or a forced mate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Score {
+    Centipawns(i32),
+
+    /// A forced mate in the given number of moves, which is negative if the
+    /// player to move is the one getting mated
+    Mate(i32),
+}
+
+impl Score {
+    /// Convert a score used inside the search, where mates count down from
+    /// `MATE` by the number of plies to reach them
+    pub fn from_value(value: i32) -> Self {
+        if value.abs() < MATE - MAX_DEPTH as i32 {
+            return Score::Centipawns(value);
+        }
+        let plies = MATE - value.abs();
+        let moves = (plies + 1) / 2;
+        Score::Mate(if value > 0 { moves } else { -moves })
+    }
+
+    /// Convert back to a score used inside the search, which orders scores
+    /// from worst to best for the player to move
+    pub fn value(self) -> i32 {
+        match self {
+            Score::Centipawns(centipawns) => centipawns,
+            Score::Mate(moves) if moves > 0 => MATE - (moves * 2 - 1),
+            Score::Mate(moves) => -(MATE + moves * 2),
+        }
+    }
+
+    /// Returns whether the score is a forced mate for either player
+    pub fn is_mate(self) -> bool {
+        matches!(self, Score::Mate(_))
+    }
+}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Score {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value().cmp(&other.value())
+    }
+}
+
+impl Display for Score {
+    /// Centipawns are shown in pawns, such as `+0.35`, and mates as `#3`, or
+    /// `#-3` for the player to move getting mated
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Score::Centipawns(centipawns) => {
+                let sign = if *centipawns < 0 { "-" } else { "+" };
+                let centipawns = centipawns.unsigned_abs();
+                write!(f, "{sign}{}.{:02}", centipawns / 100, centipawns % 100)
+            }
+            Score::Mate(moves) => write!(f, "#{moves}"),
+        }
+    }
+}
+
+/// What a search found, and how much work it took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    /// Best turn for the player to move
+    pub best: Turn,
+
+    /// Score of the best turn, for the player to move
+    pub score: Score,
+
+    /// Line of play expected to follow, starting with the best turn
+    pub pv: Vec<Turn>,
+
+    /// Depth of the deepest search that finished
+    pub depth: u32,
+
+    /// Number of positions searched
+    pub nodes: u64,
+}
+
+/// Score of one of the turns from the root, found by `analyze_all`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveAnalysis {
+    pub turn: Turn,
+
+    /// Score of the turn, for the player to move at the root
+    pub score: Score,
+
+    /// Line of play expected to follow, starting with the turn
+    pub pv: Vec<Turn>,
+}
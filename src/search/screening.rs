@@ -0,0 +1,188 @@
+use alloc::{vec, vec::Vec};
+
+use crate::game::{Board, Color, Turn};
+
+use super::{analyze_all, MoveAnalysis, Score, SearchLimits};
+
+/// Largest advantage a score counts for when comparing turns, so that a
+/// turn that misses a mate, or walks into one, doesn't outweigh every other
+/// turn in the average centipawn loss
+const SCORE_CAP: i32 = 1000;
+
+/// What `screen_games` compares a player's turns against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreeningOptions {
+    /// Depths to analyze each position to, giving a report for each
+    pub depths: Vec<u32>,
+
+    /// Number of the player's turns to skip at the start of each game, as
+    /// they are likely to come from opening preparation
+    pub skip_moves: u32,
+
+    /// Turns scoring within this many centipawns of the best turn count as
+    /// near matches, and as reasonable alternatives when weighing how
+    /// complex a position is
+    pub margin: i32,
+}
+
+impl Default for ScreeningOptions {
+    fn default() -> Self {
+        Self {
+            depths: vec![2, 4],
+            skip_moves: 8,
+            margin: 50,
+        }
+    }
+}
+
+/// How closely a player's turns matched the engine's choices when
+/// analyzing to one depth.
+///
+/// Positions with only one legal turn aren't counted. The complexity of a
+/// position is the number of turns scoring within the margin of the best,
+/// so matching the engine where there are many reasonable turns counts for
+/// more than where there is one obvious turn.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DepthReport {
+    pub depth: u32,
+
+    /// Number of positions the player's turns were compared in
+    pub positions: u32,
+
+    /// Number of the player's turns that were the engine's best
+    pub matches: u32,
+
+    /// Number of the player's turns that scored within the margin of the
+    /// engine's best
+    pub near_matches: u32,
+
+    /// Total complexity of the positions
+    pub complexity: u32,
+
+    /// Total complexity of the positions where the player's turn was the
+    /// engine's best
+    pub matched_complexity: u32,
+
+    /// Total centipawns the player's turns scored below the engine's best
+    pub centipawn_loss: u64,
+}
+
+impl DepthReport {
+    /// Returns the fraction of the player's turns that were the engine's
+    /// best
+    pub fn match_rate(&self) -> f64 {
+        ratio(self.matches as f64, self.positions as f64)
+    }
+
+    /// Returns the fraction of the player's turns that scored within the
+    /// margin of the engine's best
+    pub fn near_match_rate(&self) -> f64 {
+        ratio(self.near_matches as f64, self.positions as f64)
+    }
+
+    /// Returns the match rate with each position weighted by its complexity
+    pub fn weighted_match_rate(&self) -> f64 {
+        ratio(self.matched_complexity as f64, self.complexity as f64)
+    }
+
+    /// Returns the average number of centipawns the player's turns scored
+    /// below the engine's best
+    pub fn average_centipawn_loss(&self) -> f64 {
+        ratio(self.centipawn_loss as f64, self.positions as f64)
+    }
+
+    /// Compare the turn the player made to the engine's analysis of the
+    /// position
+    fn record(&mut self, board: &mut Board, played: Turn, margin: i32) {
+        let limits = SearchLimits {
+            depth: Some(self.depth),
+            ..Default::default()
+        };
+        let analysis = analyze_all(board, &limits);
+        let [best, _, ..] = &analysis[..] else {
+            return;
+        };
+        let Some(turn) = analysis.iter().find(|analysis| analysis.turn == played) else {
+            return;
+        };
+        let loss = |analysis: &MoveAnalysis| capped(best.score) - capped(analysis.score);
+        let complexity = analysis
+            .iter()
+            .filter(|analysis| loss(analysis) <= margin)
+            .count() as u32;
+        self.positions += 1;
+        self.complexity += complexity;
+        self.centipawn_loss += loss(turn) as u64;
+        if best.turn == played {
+            self.matches += 1;
+            self.matched_complexity += complexity;
+        }
+        if loss(turn) <= margin {
+            self.near_matches += 1;
+        }
+    }
+}
+
+/// Compare a player's turns in a set of finished games to the turns
+/// `analyze_all` scores best, for each depth in the options.
+///
+/// Each game is given as the board at its end, along with the color the
+/// player had in it. This is meant as a first screen for fair play, such
+/// as at online club events: a high match rate or low centipawn loss
+/// across many games is a reason for an arbiter to look closer, not proof
+/// of cheating, as strong players match engines often in simple positions.
+pub fn screen_games<'a>(
+    games: impl IntoIterator<Item = (&'a Board, Color)>,
+    options: &ScreeningOptions,
+) -> Vec<DepthReport> {
+    let mut reports: Vec<DepthReport> = options
+        .depths
+        .iter()
+        .map(|&depth| DepthReport {
+            depth,
+            ..Default::default()
+        })
+        .collect();
+    for (game, player) in games {
+        let (mut board, turns) = rewind(game);
+        let mut moves = 0;
+        for turn in turns {
+            if board.whose_turn() == player {
+                moves += 1;
+                if moves > options.skip_moves {
+                    for report in &mut reports {
+                        report.record(&mut board, turn, options.margin);
+                    }
+                }
+            }
+            board.make_turn(turn);
+        }
+    }
+    reports
+}
+
+/// Returns a copy of the board at the start of its game, along with the
+/// turns made since then, in order
+fn rewind(board: &Board) -> (Board, Vec<Turn>) {
+    let mut board = board.clone();
+    board.resume();
+    let mut turns = Vec::new();
+    while let Some(turn) = board.undo_turn() {
+        turns.push(turn);
+    }
+    turns.reverse();
+    (board, turns)
+}
+
+/// Returns the value of a score, limited to `SCORE_CAP` either way
+fn capped(score: Score) -> i32 {
+    score.value().clamp(-SCORE_CAP, SCORE_CAP)
+}
+
+/// Returns `numerator / denominator`, or zero if there is nothing to divide
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator == 0.0 {
+        return 0.0;
+    }
+    numerator / denominator
+}
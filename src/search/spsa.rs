@@ -0,0 +1,170 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::game::Board;
+
+use super::{play_match, rng::Rng, Engine, MatchResult, SearchLimits, DEFAULT_MAX_PLIES};
+
+/// A parameter being tuned
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpsaParam {
+    pub name: String,
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+
+    /// How far the value is moved either way when comparing, at the first
+    /// iteration. This shrinks slowly as tuning goes on.
+    pub step: f64,
+}
+
+impl SpsaParam {
+    pub fn new(name: impl Into<String>, value: f64, min: f64, max: f64, step: f64) -> Self {
+        Self {
+            name: name.into(),
+            value,
+            min,
+            max,
+            step,
+        }
+    }
+}
+
+/// Tunes search and evaluation parameters by simultaneous perturbation
+/// stochastic approximation (SPSA).
+///
+/// Each iteration moves every parameter a step up or down at random, plays
+/// a match between an engine with the values moved one way and an engine
+/// with them moved the other, then moves the values towards whichever did
+/// better. Engines are created from the parameter values by a function
+/// given to `step` or `tune`, so anything an engine can be built from can
+/// be tuned. This needs the `std` feature.
+#[derive(Debug, Clone)]
+pub struct Spsa {
+    pub params: Vec<SpsaParam>,
+
+    /// How far the values move for each game won more than lost, as a
+    /// fraction of the step, at the first iteration
+    pub learning_rate: f64,
+
+    /// How quickly the learning rate falls as iterations go on
+    pub alpha: f64,
+
+    /// How quickly the steps fall as iterations go on
+    pub gamma: f64,
+
+    /// Number of iterations added to the count when working out the
+    /// learning rate, which keeps early iterations from moving too far
+    pub stability: f64,
+
+    /// Limits each engine searches within on every turn
+    pub limits: SearchLimits,
+
+    /// Number of plies after which a game is counted as a draw
+    pub max_plies: u32,
+
+    /// Number of iterations done so far
+    iteration: u32,
+
+    rng: Rng,
+}
+
+impl Spsa {
+    /// Create a tuner with the commonly used settings, with each engine
+    /// searching to depth 2
+    pub fn new(params: Vec<SpsaParam>) -> Self {
+        Self {
+            params,
+            learning_rate: 0.002,
+            alpha: 0.602,
+            gamma: 0.101,
+            stability: 10.0,
+            limits: SearchLimits {
+                depth: Some(2),
+                ..SearchLimits::default()
+            },
+            max_plies: DEFAULT_MAX_PLIES,
+            iteration: 0,
+            rng: Rng::new(0),
+        }
+    }
+
+    /// Seed the random number generator used to choose which way each
+    /// parameter is moved
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    /// Returns the current value of each parameter
+    pub fn values(&self) -> Vec<f64> {
+        self.params.iter().map(|param| param.value).collect()
+    }
+
+    /// Returns the number of iterations done so far
+    pub fn iteration(&self) -> u32 {
+        self.iteration
+    }
+
+    /// Run one iteration, playing a game from each opening as each color,
+    /// and return the result of the engine with the values moved up
+    pub fn step<E: Engine>(
+        &mut self,
+        openings: &[Board],
+        mut engine: impl FnMut(&[f64]) -> E,
+    ) -> MatchResult {
+        let k = self.iteration as f64;
+        let learning_rate = self.learning_rate
+            / ((k + 1.0 + self.stability) / (1.0 + self.stability)).powf(self.alpha);
+        let shrink = (k + 1.0).powf(self.gamma);
+        let signs: Vec<f64> = self
+            .params
+            .iter()
+            .map(|_| {
+                if self.rng.next_u64() & 1 == 0 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            })
+            .collect();
+        let moved = |direction: f64| -> Vec<f64> {
+            self.params
+                .iter()
+                .zip(&signs)
+                .map(|(param, sign)| {
+                    let value = param.value + direction * sign * param.step / shrink;
+                    value.clamp(param.min, param.max)
+                })
+                .collect()
+        };
+        let mut plus = engine(&moved(1.0));
+        let mut minus = engine(&moved(-1.0));
+        let result = play_match(
+            &mut plus,
+            &mut minus,
+            openings,
+            &self.limits,
+            self.max_plies,
+        );
+        let net = result.wins as f64 - result.losses as f64;
+        for (param, sign) in self.params.iter_mut().zip(&signs) {
+            let step = param.step / shrink;
+            param.value =
+                (param.value + learning_rate * step * net * sign).clamp(param.min, param.max);
+        }
+        self.iteration += 1;
+        result
+    }
+
+    /// Run the given number of iterations, returning the final values
+    pub fn tune<E: Engine>(
+        &mut self,
+        iterations: u32,
+        openings: &[Board],
+        mut engine: impl FnMut(&[f64]) -> E,
+    ) -> Vec<f64> {
+        for _ in 0..iterations {
+            self.step(openings, &mut engine);
+        }
+        self.values()
+    }
+}
@@ -0,0 +1,315 @@
+use alloc::{vec, vec::Vec};
+
+use crate::game::{
+    bishop_attacks, king_attacks, pawn_attacks, rook_attacks, Bitboard, Board, Color, PieceType,
+    Position,
+};
+
+use super::{DRAW, MATE};
+
+/// Entry for a position whose result isn't known yet, or is a draw once
+/// generation has finished
+const UNKNOWN: u8 = 0;
+
+/// Entry for a position that can't happen in a game
+const ILLEGAL: u8 = u8::MAX;
+
+/// Number of entries in each table, for every combination of side to move,
+/// strong king, strong piece and weak king square
+const TABLE_SIZE: usize = 2 * 64 * 64 * 64;
+
+/// An ending with a king and one other piece against a lone king
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ending {
+    /// King and queen against king
+    Queen,
+
+    /// King and rook against king
+    Rook,
+
+    /// King and pawn against king
+    Pawn,
+}
+
+impl Ending {
+    /// Returns the kind of piece the strong side has besides its king
+    pub fn kind(self) -> PieceType {
+        match self {
+            Ending::Queen => PieceType::Queen,
+            Ending::Rook => PieceType::Rook,
+            Ending::Pawn => PieceType::Pawn,
+        }
+    }
+
+    /// Returns the ending with the given kind of piece, if there is one
+    pub fn from_kind(kind: PieceType) -> Option<Self> {
+        match kind {
+            PieceType::Queen => Some(Ending::Queen),
+            PieceType::Rook => Some(Ending::Rook),
+            PieceType::Pawn => Some(Ending::Pawn),
+            _ => None,
+        }
+    }
+}
+
+/// Result of a position with perfect play, from the point of view of the
+/// player to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TablebaseResult {
+    /// The player to move mates in the given number of plies
+    Win(u32),
+
+    /// The player to move is mated in the given number of plies
+    Loss(u32),
+
+    Draw,
+}
+
+impl TablebaseResult {
+    /// Returns the result as a search score
+    pub fn score(self) -> i32 {
+        match self {
+            TablebaseResult::Win(plies) => MATE - plies as i32,
+            TablebaseResult::Loss(plies) => -(MATE - plies as i32),
+            TablebaseResult::Draw => DRAW,
+        }
+    }
+}
+
+/// Exact results of the simplest endings, generated when created rather
+/// than read from files.
+///
+/// Each table gives the number of plies to mate for every position of its
+/// ending, found by working backwards from every mate (retrograde
+/// analysis). The 50 move rule isn't taken into account.
+#[derive(Debug, Clone)]
+pub struct Tablebase {
+    /// Tables indexed by `index`, in the order of `Ending`, storing one more
+    /// than the number of plies to mate for won positions
+    tables: [Vec<u8>; 3],
+}
+
+impl Tablebase {
+    /// Generate the tables for every ending, which takes around a second
+    pub fn generate() -> Self {
+        let queen = generate(Ending::Queen, &[], &[]);
+        let rook = generate(Ending::Rook, &[], &[]);
+        let pawn = generate(Ending::Pawn, &queen, &rook);
+        Self {
+            tables: [queen, rook, pawn],
+        }
+    }
+
+    /// Look up the result of the position, if it is one of the endings and
+    /// neither player can castle
+    pub fn probe(&self, board: &Board) -> Option<TablebaseResult> {
+        if board.occupied().count() != 3
+            || board.castling_rights().can_castle_any(Color::White)
+            || board.castling_rights().can_castle_any(Color::Black)
+        {
+            return None;
+        }
+        // The strong side is the one with a piece besides its king
+        let strong = if board.occupied_by(Color::White).count() == 2 {
+            Color::White
+        } else {
+            Color::Black
+        };
+        let piece = (board.occupied_by(strong) & !board.pieces(PieceType::King, strong)).first()?;
+        let ending = Ending::from_kind(board.at_position(piece)?.kind)?;
+        // Tables are stored with White as the strong side, so flip the board
+        // if it is Black
+        let orient = |pos: Position| match strong {
+            Color::White => pos,
+            Color::Black => Position::new(7 - pos.row(), pos.col()),
+        };
+        let strong_king = board.pieces(PieceType::King, strong).first()?;
+        let weak_king = board.pieces(PieceType::King, !strong).first()?;
+        let strong_to_move = board.whose_turn() == strong;
+        let entry = self.tables[ending as usize][index(
+            strong_to_move,
+            orient(strong_king),
+            orient(piece),
+            orient(weak_king),
+        )];
+        Some(match entry {
+            UNKNOWN | ILLEGAL => TablebaseResult::Draw,
+            entry if strong_to_move => TablebaseResult::Win(entry as u32 - 1),
+            entry => TablebaseResult::Loss(entry as u32 - 1),
+        })
+    }
+}
+
+/// Returns the index into a table of a position with White as the strong
+/// side
+fn index(
+    strong_to_move: bool,
+    strong_king: Position,
+    piece: Position,
+    weak_king: Position,
+) -> usize {
+    ((strong_to_move as usize * 64 + strong_king.pos()) * 64 + piece.pos()) * 64 + weak_king.pos()
+}
+
+/// Splits an index back into the position it is for
+fn position(index: usize) -> (bool, Position, Position, Position) {
+    let square = |i: usize| Position::new((i / 8) as i8, (i % 8) as i8);
+    (
+        index / (64 * 64 * 64) == 1,
+        square(index / (64 * 64) % 64),
+        square(index / 64 % 64),
+        square(index % 64),
+    )
+}
+
+/// Returns the squares attacked by the strong piece
+fn piece_attacks(kind: PieceType, piece: Position, occupied: Bitboard) -> Bitboard {
+    match kind {
+        PieceType::Queen => rook_attacks(piece, occupied) | bishop_attacks(piece, occupied),
+        PieceType::Rook => rook_attacks(piece, occupied),
+        _ => pawn_attacks(Color::White, piece),
+    }
+}
+
+/// Generates the table for one ending, given the tables that pawns can
+/// promote into
+struct Generator<'a> {
+    kind: PieceType,
+    table: Vec<u8>,
+    queen: &'a [u8],
+    rook: &'a [u8],
+}
+
+impl Generator<'_> {
+    /// Returns whether the position can happen in a game
+    fn is_legal(&self, strong_to_move: bool, sk: Position, piece: Position, wk: Position) -> bool {
+        if sk == piece || sk == wk || piece == wk || king_attacks(sk).contains(wk) {
+            return false;
+        }
+        if self.kind == PieceType::Pawn && !(1..7).contains(&piece.row()) {
+            return false;
+        }
+        // The player who just moved can't have left their opponent in check
+        let occupied = Bitboard::from_position(sk) | Bitboard::from_position(wk);
+        !(strong_to_move && piece_attacks(self.kind, piece, occupied).contains(wk))
+    }
+
+    /// Returns the entries of the positions after each turn of the strong
+    /// side, with the weak side to move
+    fn strong_turns(&self, sk: Position, piece: Position, wk: Position) -> Vec<u8> {
+        let mut entries = vec![];
+        for to in king_attacks(sk) & !king_attacks(wk) & !Bitboard::from_position(piece) {
+            entries.push(self.table[index(false, to, piece, wk)]);
+        }
+        let occupied = Bitboard::from_position(sk) | Bitboard::from_position(wk);
+        if self.kind != PieceType::Pawn {
+            for to in piece_attacks(self.kind, piece, occupied) & !occupied {
+                entries.push(self.table[index(false, sk, to, wk)]);
+            }
+            return entries;
+        }
+        let push = Position::new(piece.row() + 1, piece.col());
+        if occupied.contains(push) {
+            return entries;
+        }
+        if push.row() == 7 {
+            // Promoting to a bishop or knight can only draw
+            entries.push(self.queen[index(false, sk, push, wk)]);
+            entries.push(self.rook[index(false, sk, push, wk)]);
+            return entries;
+        }
+        entries.push(self.table[index(false, sk, push, wk)]);
+        if piece.row() == 1 {
+            let double = Position::new(3, piece.col());
+            if !occupied.contains(double) {
+                entries.push(self.table[index(false, sk, double, wk)]);
+            }
+        }
+        entries
+    }
+
+    /// Returns the entries of the positions after each turn of the weak
+    /// side, with the strong side to move, or `None` if it can capture the
+    /// strong piece, which draws
+    fn weak_turns(&self, sk: Position, piece: Position, wk: Position) -> Option<Vec<u8>> {
+        let occupied = Bitboard::from_position(sk) | Bitboard::from_position(piece);
+        let attacked = king_attacks(sk) | piece_attacks(self.kind, piece, occupied);
+        let mut entries = vec![];
+        for to in king_attacks(wk) & !king_attacks(sk) {
+            if to == piece {
+                return None;
+            }
+            if !attacked.contains(to) {
+                entries.push(self.table[index(true, sk, piece, to)]);
+            }
+        }
+        Some(entries)
+    }
+}
+
+/// Generate the table for an ending, given the tables for a queen and a
+/// rook for pawns to promote into
+fn generate(ending: Ending, queen: &[u8], rook: &[u8]) -> Vec<u8> {
+    let mut generator = Generator {
+        kind: ending.kind(),
+        table: vec![UNKNOWN; TABLE_SIZE],
+        queen,
+        rook,
+    };
+    // Start with every mate
+    for i in 0..TABLE_SIZE {
+        let (strong_to_move, sk, piece, wk) = position(i);
+        generator.table[i] = if !generator.is_legal(strong_to_move, sk, piece, wk) {
+            ILLEGAL
+        } else if strong_to_move {
+            UNKNOWN
+        } else {
+            let occupied = Bitboard::from_position(sk) | Bitboard::from_position(wk);
+            let check = piece_attacks(generator.kind, piece, occupied).contains(wk);
+            match generator.weak_turns(sk, piece, wk) {
+                Some(turns) if turns.is_empty() && check => 1,
+                _ => UNKNOWN,
+            }
+        };
+    }
+    // Then work backwards a ply at a time, with the strong side winning if
+    // any turn leads to a win, and the weak side losing if every turn does,
+    // until nothing changes for a whole move. Pawn endings carry on until
+    // every promotion has been reached, however long it takes to mate.
+    let horizon = queen
+        .iter()
+        .chain(rook)
+        .filter(|&&entry| entry != ILLEGAL)
+        .max()
+        .map_or(0, |&entry| entry as usize);
+    let mut unchanged = 0;
+    let mut ply = 1;
+    while unchanged < 2 || ply <= horizon {
+        let strong_to_move = ply % 2 == 1;
+        let won = |entry: u8| entry != UNKNOWN && entry != ILLEGAL && entry as usize <= ply;
+        let mut changed = false;
+        for i in 0..TABLE_SIZE {
+            if generator.table[i] != UNKNOWN {
+                continue;
+            }
+            let (to_move, sk, piece, wk) = position(i);
+            if to_move != strong_to_move {
+                continue;
+            }
+            let wins = if strong_to_move {
+                generator.strong_turns(sk, piece, wk).into_iter().any(won)
+            } else {
+                generator
+                    .weak_turns(sk, piece, wk)
+                    .is_some_and(|turns| !turns.is_empty() && turns.into_iter().all(won))
+            };
+            if wins {
+                generator.table[i] = ply as u8 + 1;
+                changed = true;
+            }
+        }
+        unchanged = if changed { 0 } else { unchanged + 1 };
+        ply += 1;
+    }
+    generator.table
+}
@@ -0,0 +1,91 @@
+use core::time::Duration;
+
+use crate::game::Turn;
+
+/// Time kept back from every move, for the time spent outside the search
+const MOVE_OVERHEAD: Duration = Duration::from_millis(20);
+
+/// Number of moves the remaining time is shared between, if the time
+/// control doesn't say
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+/// Number of iterations the best turn must stay the same for the soft limit
+/// to be cut as far as it goes
+const MAX_STABILITY: u32 = 5;
+
+/// The clock of the player to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Clock {
+    /// Time left on the clock
+    pub time: Duration,
+
+    /// Time added to the clock after each move
+    pub increment: Duration,
+
+    /// Number of moves until more time is added, if the time control has
+    /// them
+    pub moves_to_go: Option<u32>,
+}
+
+/// Decides how long to spend on a move, given the clock.
+///
+/// A search should stop once the hard limit is reached. Between
+/// iterations, it should also stop once `should_stop` says so, which
+/// happens after the soft limit, or sooner if the best turn hasn't changed
+/// for a while. Neither limit is more than half of the time left, so a
+/// move never uses the whole clock, even with one move to go. This doesn't
+/// measure time itself, so can be used by any search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeManager {
+    soft: Duration,
+    hard: Duration,
+
+    /// Best turn after the last iteration
+    best: Option<Turn>,
+
+    /// Number of iterations in a row the best turn has stayed the same
+    stability: u32,
+}
+
+impl TimeManager {
+    pub fn new(clock: &Clock) -> Self {
+        let available = clock.time.saturating_sub(MOVE_OVERHEAD);
+        let moves_to_go = clock.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+        let soft = (available / moves_to_go + clock.increment * 3 / 4).min(available / 2);
+        let hard = (soft * 3).min(available / 2);
+        Self {
+            soft,
+            hard,
+            best: None,
+            stability: 0,
+        }
+    }
+
+    /// Returns the time after which an iteration shouldn't be started,
+    /// before it is cut for a stable best turn
+    pub fn soft_limit(&self) -> Duration {
+        self.soft
+    }
+
+    /// Returns the time after which the search must stop, even in the
+    /// middle of an iteration
+    pub fn hard_limit(&self) -> Duration {
+        self.hard
+    }
+
+    /// Record the best turn found by an iteration that has just finished,
+    /// and return whether the search should stop, given the time it has
+    /// taken so far.
+    ///
+    /// Each iteration in a row with the same best turn cuts the soft limit
+    /// by a tenth, down to half of it.
+    pub fn should_stop(&mut self, best: Turn, elapsed: Duration) -> bool {
+        if self.best == Some(best) {
+            self.stability = (self.stability + 1).min(MAX_STABILITY);
+        } else {
+            self.best = Some(best);
+            self.stability = 0;
+        }
+        elapsed >= self.soft * (10 - self.stability) / 10 || elapsed >= self.hard
+    }
+}
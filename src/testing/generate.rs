@@ -0,0 +1,89 @@
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{game::Board, search::rng::Rng};
+
+/// Plays random legal games, to check code that handles games against many
+/// positions it wasn't written with in mind.
+///
+/// Games are the same for the same seed. As an iterator, this gives a new
+/// game each time.
+#[derive(Debug, Clone)]
+pub struct GameGenerator {
+    rng: Rng,
+
+    /// Most plies to play in each game, which ends sooner if it is over
+    pub max_plies: usize,
+
+    /// Whether games start from random Chess960 starting positions rather
+    /// than the usual one
+    pub chess960: bool,
+}
+
+impl GameGenerator {
+    /// Create a generator playing games of up to 200 plies from the usual
+    /// starting position
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            max_plies: 200,
+            chess960: false,
+        }
+    }
+
+    /// Returns a starting position for a game, which is a random Chess960
+    /// one if `chess960` is set
+    pub fn start_position(&mut self) -> Board {
+        if !self.chess960 {
+            return Board::from_start();
+        }
+        let pieces = self.chess960_back_rank();
+        let fen = format!(
+            "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1",
+            pieces.to_ascii_lowercase(),
+            pieces
+        );
+        Board::from_fen(&fen).expect("Chess960 starting positions are valid")
+    }
+
+    /// Play a random game, returning the board after its last turn, from
+    /// which the rest of the game can be found by undoing turns
+    pub fn game(&mut self) -> Board {
+        let mut board = self.start_position();
+        for _ in 0..self.max_plies {
+            let turns = board.get_moves();
+            if turns.is_empty() {
+                break;
+            }
+            board.make_turn(turns[self.rng.below(turns.len())]);
+        }
+        board
+    }
+
+    /// Returns the white pieces on the back rank of a random Chess960
+    /// starting position, such as `RNBQKBNR`
+    fn chess960_back_rank(&mut self) -> String {
+        let mut rank = [' '; 8];
+        // Bishops go on squares of different colors
+        rank[self.rng.below(4) * 2] = 'B';
+        rank[self.rng.below(4) * 2 + 1] = 'B';
+        // The queen and knights go on any of the squares that are left, and
+        // the king goes between the rooks on the last three
+        for piece in ['Q', 'N', 'N'] {
+            let empty: Vec<usize> = (0..8).filter(|&col| rank[col] == ' ').collect();
+            rank[empty[self.rng.below(empty.len())]] = piece;
+        }
+        for piece in ['R', 'K', 'R'] {
+            let col = rank.iter().position(|&c| c == ' ').unwrap();
+            rank[col] = piece;
+        }
+        rank.iter().collect()
+    }
+}
+
+impl Iterator for GameGenerator {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.game())
+    }
+}
@@ -0,0 +1,13 @@
+//! Support for testing code that uses this crate, and the crate itself.
+//!
+//! `GameGenerator` plays random legal games, and the `check_` functions
+//! check that positions, turns and games read back from each notation they
+//! can be written in.
+
+mod generate;
+mod round_trip;
+
+pub use generate::GameGenerator;
+#[cfg(feature = "std")]
+pub use round_trip::check_pgn;
+pub use round_trip::{check_fen, check_game, check_turns, RoundTripError};
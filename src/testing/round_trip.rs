@@ -0,0 +1,137 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{error::Error, fmt::Display};
+
+use crate::game::{Board, Turn};
+#[cfg(feature = "std")]
+use crate::game::{PgnGame, PgnReader};
+
+/// A notation that didn't give back what was written in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoundTripError {
+    /// Reading the FEN of a position gave a different position
+    /// Includes the FEN that was written, and the FEN of the position read
+    /// from it, or why it couldn't be read
+    Fen { written: String, read: String },
+
+    /// Reading the SAN of a turn gave a different turn, or none
+    /// Includes the FEN of the position and the SAN of the turn
+    San { fen: String, san: String },
+
+    /// Reading the UCI notation of a turn gave a different turn, or none
+    /// Includes the FEN of the position and the UCI notation of the turn
+    Uci { fen: String, uci: String },
+
+    /// Reading a game written as PGN gave a different game, or none
+    /// Includes the PGN that was written
+    Pgn(String),
+}
+
+impl Display for RoundTripError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RoundTripError::Fen { written, read } => {
+                write!(f, "FEN '{}' was read as '{}'", written, read)
+            }
+            RoundTripError::San { fen, san } => {
+                write!(f, "SAN '{}' wasn't read as the same turn in '{}'", san, fen)
+            }
+            RoundTripError::Uci { fen, uci } => {
+                write!(f, "UCI '{}' wasn't read as the same turn in '{}'", uci, fen)
+            }
+            RoundTripError::Pgn(pgn) => write!(f, "PGN wasn't read as the same game:\n{}", pgn),
+        }
+    }
+}
+
+impl Error for RoundTripError {}
+
+/// Check that the position reads back from its FEN, with the same pieces,
+/// castling rights, en passant target and clocks
+pub fn check_fen(board: &Board) -> Result<(), RoundTripError> {
+    let written = board.to_fen();
+    let read = match Board::from_fen(&written) {
+        Ok(read) if read.zobrist_hash() != board.zobrist_hash() => {
+            format!("{} (with a different hash)", read.to_fen())
+        }
+        Ok(read) => read.to_fen(),
+        Err(e) => e.to_string(),
+    };
+    if read != written {
+        return Err(RoundTripError::Fen { written, read });
+    }
+    Ok(())
+}
+
+/// Check that every legal turn in the position reads back from its SAN and
+/// its UCI notation
+pub fn check_turns(board: &mut Board) -> Result<(), RoundTripError> {
+    for turn in board.get_moves() {
+        let san = board.turn_to_san(&turn);
+        if board.parse_san(&san) != Some(turn) {
+            return Err(RoundTripError::San {
+                fen: board.to_fen(),
+                san,
+            });
+        }
+        let uci = board.turn_to_uci(&turn);
+        if board.parse_uci(&uci) != Some(turn) {
+            return Err(RoundTripError::Uci {
+                fen: board.to_fen(),
+                uci,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Check every position of a game with `check_fen` and `check_turns`, from
+/// its start up to the board's current position
+pub fn check_game(board: &Board) -> Result<(), RoundTripError> {
+    let (mut board, turns) = rewind(board);
+    for turn in turns {
+        check_fen(&board)?;
+        check_turns(&mut board)?;
+        board.make_turn(turn);
+    }
+    check_fen(&board)?;
+    check_turns(&mut board)
+}
+
+/// Check that the game reads back from its PGN, with the same turns and
+/// result.
+///
+/// This needs the `std` feature.
+#[cfg(feature = "std")]
+pub fn check_pgn(board: &Board) -> Result<(), RoundTripError> {
+    let game = PgnGame::from_board(board.clone());
+    let pgn = game.to_string();
+    let same = match PgnReader::new(pgn.as_bytes()).next() {
+        Some(Ok(read)) => {
+            read.tags == game.tags
+                && read.board.to_fen() == board.to_fen()
+                && rewind(&read.board).1 == rewind(board).1
+        }
+        _ => false,
+    };
+    if !same {
+        return Err(RoundTripError::Pgn(pgn));
+    }
+    Ok(())
+}
+
+/// Returns a copy of the board at the start of its game, along with the
+/// turns made since then, in order
+fn rewind(board: &Board) -> (Board, Vec<Turn>) {
+    let mut board = board.clone();
+    board.resume();
+    let mut turns = Vec::new();
+    while let Some(turn) = board.undo_turn() {
+        turns.push(turn);
+    }
+    turns.reverse();
+    (board, turns)
+}
@@ -0,0 +1,396 @@
+use chs::game::{
+    Bitboard, Board, ClaimError, Color, DrawReason, GameState, MoveError, PieceType, Position,
+    Rules, RulesError, WinReason,
+};
+
+fn board(fen: &str) -> Board {
+    Board::from_fen(fen).unwrap()
+}
+
+#[test]
+fn lone_king_has_no_mating_material() {
+    let board = board("k7/8/1K6/8/8/8/8/8 w - - 0 1");
+    assert!(!board.has_mating_material(Color::White));
+    assert!(!board.has_mating_material(Color::Black));
+}
+
+#[test]
+fn lone_minor_piece_cant_mate_a_lone_king() {
+    assert!(!board("k7/8/1K6/8/8/8/8/5B2 w - - 0 1").has_mating_material(Color::White));
+    assert!(!board("k7/8/1K6/8/8/8/8/6N1 w - - 0 1").has_mating_material(Color::White));
+}
+
+#[test]
+fn bishop_can_mate_king_blocked_by_rook() {
+    // Bd5# with the rook on b8 blocking its own king
+    let board = board("kr6/8/1K6/8/8/8/8/5B2 w - - 0 1");
+    assert!(board.has_mating_material(Color::White));
+}
+
+#[test]
+fn knight_can_mate_king_blocked_by_queen() {
+    let board = board("7k/6q1/8/8/8/8/8/K5N1 w - - 0 1");
+    assert!(board.has_mating_material(Color::White));
+}
+
+#[test]
+fn bishop_cant_mate_with_only_same_colored_opposing_bishops() {
+    let board = board("k7/8/1K6/8/8/8/4b3/5B2 w - - 0 1");
+    assert!(!board.has_mating_material(Color::White));
+}
+
+#[test]
+fn bishop_can_mate_king_blocked_by_bishop_on_other_color() {
+    let board = board("k7/8/1K6/8/8/8/5b2/5B2 w - - 0 1");
+    assert!(board.has_mating_material(Color::White));
+}
+
+#[test]
+fn timing_out_against_mating_material_loses() {
+    let mut board = board("kr6/8/1K6/8/8/8/8/5B2 b - - 0 1");
+    board.time_out(Color::Black).unwrap();
+    assert_eq!(
+        board.get_game_state(),
+        GameState::Win(Color::White, WinReason::TimeOut)
+    );
+}
+
+#[test]
+fn timing_out_against_a_lone_king_draws() {
+    let mut board = board("k7/8/1K6/8/8/8/8/5R2 b - - 0 1");
+    board.time_out(Color::White).unwrap();
+    assert_eq!(board.get_game_state(), GameState::Draw(DrawReason::TimeOut));
+}
+
+#[test]
+fn resignation_survives_undo() {
+    let mut board = Board::from_start();
+    board.apply_movetext("1. e4 e5").unwrap();
+    board.resign(Color::Black).unwrap();
+    board.undo_turn();
+    assert_eq!(
+        board.get_game_state(),
+        GameState::Win(Color::White, WinReason::Resigned)
+    );
+    assert_eq!(
+        board.resume(),
+        Some(GameState::Win(Color::White, WinReason::Resigned))
+    );
+    assert_eq!(board.get_game_state(), GameState::Playing);
+}
+
+#[test]
+fn ended_game_cant_be_ended_again() {
+    let mut board = Board::from_start();
+    board.resign(Color::White).unwrap();
+    assert_eq!(board.agree_draw(), Err(MoveError::GameOver));
+    assert_eq!(board.time_out(Color::Black), Err(MoveError::GameOver));
+    assert_eq!(board.resign(Color::Black), Err(MoveError::GameOver));
+    assert_eq!(
+        board.get_game_state(),
+        GameState::Win(Color::Black, WinReason::Resigned)
+    );
+
+    let mut board = Board::from_start();
+    board.apply_movetext("1. f3 e5 2. g4 Qh4#").unwrap();
+    assert_eq!(board.resign(Color::Black), Err(MoveError::GameOver));
+}
+
+#[test]
+fn movetext_includes_result() {
+    let mut board = Board::from_start();
+    board.apply_movetext("1. e4 e5").unwrap();
+    assert_eq!(board.movetext(), "1. e4 e5");
+    board.agree_draw().unwrap();
+    assert_eq!(board.movetext(), "1. e4 e5 1/2-1/2");
+    board.undo_turn();
+    assert_eq!(board.movetext(), "1. e4 1/2-1/2");
+
+    let mut board = Board::from_start();
+    board.apply_movetext("1. f3 e5 2. g4 Qh4#").unwrap();
+    assert_eq!(board.movetext(), "1. f3 e5 2. g4 Qh4# 0-1");
+}
+
+#[test]
+fn movetext_accepts_castling_with_zeros() {
+    let mut board = Board::from_start();
+    board
+        .apply_movetext("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. 0-0 d6 5.d3 Bg4 6. Nc3 Qd7 7. Be3 0-0-0")
+        .unwrap();
+    assert_eq!(
+        board.to_fen(),
+        "2kr2nr/pppq1ppp/2np4/2b1p3/2B1P1b1/2NPBN2/PPP2PPP/R2Q1RK1 w - - 5 8"
+    );
+}
+
+#[test]
+fn threefold_repetition_draws() {
+    let mut board = Board::from_start();
+    board
+        .apply_movetext("1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3 Nf6 4. Ng1")
+        .unwrap();
+    assert_eq!(board.get_game_state(), GameState::Playing);
+    board.apply_movetext("4... Ng8").unwrap();
+    assert_eq!(
+        board.get_game_state(),
+        GameState::Draw(DrawReason::ThreefoldRepetition)
+    );
+    board.undo_turn();
+    assert_eq!(board.get_game_state(), GameState::Playing);
+}
+
+#[test]
+fn custom_limits_are_named_after_the_limit() {
+    let mut repeated = Board::from_start();
+    repeated.set_rules(Rules {
+        repetition_limit: 2,
+        ..Default::default()
+    });
+    repeated.apply_movetext("1. Nf3 Nf6 2. Ng1 Ng8").unwrap();
+    assert_eq!(
+        repeated.get_game_state(),
+        GameState::Draw(DrawReason::RepetitionLimit(2))
+    );
+
+    let mut board = board("4k3/8/8/8/8/8/8/R3K3 w - - 80 60");
+    board.set_rules(Rules {
+        move_limit: 40,
+        ..Default::default()
+    });
+    assert_eq!(
+        board.get_game_state(),
+        GameState::Draw(DrawReason::MoveLimit(40))
+    );
+    board.set_rules(Rules::default());
+    assert_eq!(board.get_game_state(), GameState::Playing);
+}
+
+#[test]
+fn rules_reject_limits_that_draw_straight_away() {
+    assert_eq!(Rules::new(0, 3, true), Err(RulesError::MoveLimit(0)));
+    assert_eq!(Rules::new(50, 0, true), Err(RulesError::RepetitionLimit(0)));
+    assert_eq!(Rules::new(50, 1, true), Err(RulesError::RepetitionLimit(1)));
+    assert_eq!(Rules::new(50, 3, true), Ok(Rules::default()));
+
+    // The smallest limits allowed still let the game start
+    let mut board = Board::from_start();
+    board.set_rules(Rules::new(1, 2, true).unwrap());
+    assert_eq!(board.get_game_state(), GameState::Playing);
+    board.apply_movetext("1. Nf3").unwrap();
+    assert_eq!(board.get_game_state(), GameState::Playing);
+    board.apply_movetext("1... Nf6").unwrap();
+    assert_eq!(
+        board.get_game_state(),
+        GameState::Draw(DrawReason::MoveLimit(1))
+    );
+}
+
+#[test]
+fn limits_that_match_the_laws_are_named_after_them() {
+    let mut board = board("4k3/8/8/8/8/8/8/R3K3 w - - 100 60");
+    assert_eq!(
+        board.get_game_state(),
+        GameState::Draw(DrawReason::FiftyMoveRule)
+    );
+    board.set_rules(Rules::without_claims());
+    assert_eq!(board.get_game_state(), GameState::Playing);
+}
+
+fn squares(names: &[&str]) -> Bitboard {
+    names
+        .iter()
+        .map(|name| name.parse::<Position>().unwrap())
+        .fold(Bitboard::EMPTY, |squares, pos| {
+            squares | Bitboard::from_position(pos)
+        })
+}
+
+#[test]
+fn region_masks() {
+    let d4 = "d4".parse().unwrap();
+    assert_eq!(
+        Bitboard::file(3),
+        squares(&["d1", "d2", "d3", "d4", "d5", "d6", "d7", "d8"])
+    );
+    assert_eq!(
+        Bitboard::rank(7),
+        squares(&["a8", "b8", "c8", "d8", "e8", "f8", "g8", "h8"])
+    );
+    assert_eq!(Bitboard::adjacent_files(0), Bitboard::file(1));
+    assert_eq!(
+        Bitboard::diagonal("b1".parse().unwrap()),
+        squares(&["b1", "c2", "d3", "e4", "f5", "g6", "h7"])
+    );
+    assert_eq!(
+        Bitboard::anti_diagonal(d4),
+        squares(&["a7", "b6", "c5", "d4", "e3", "f2", "g1"])
+    );
+    assert_eq!(
+        Bitboard::forward_span(d4, Color::White),
+        squares(&["d5", "d6", "d7", "d8"])
+    );
+    assert_eq!(
+        Bitboard::forward_span(d4, Color::Black),
+        squares(&["d1", "d2", "d3"])
+    );
+    assert_eq!(
+        Bitboard::forward_span("d8".parse().unwrap(), Color::White),
+        Bitboard::EMPTY
+    );
+}
+
+#[test]
+fn pieces_in_regions() {
+    let board = board("4k3/3p4/8/3P4/8/8/8/3K4 w - - 0 1");
+    assert_eq!(board.pieces_on_file(3), squares(&["d7", "d5", "d1"]));
+    assert_eq!(board.pieces_on_rank(7), squares(&["e8"]));
+    assert_eq!(
+        board.pieces_in_king_zone(Color::Black),
+        squares(&["e8", "d7"])
+    );
+    assert_eq!(
+        board.pawns_in_front("d5".parse().unwrap(), Color::White, Color::Black),
+        squares(&["d7"])
+    );
+}
+
+#[test]
+fn fen_counters_must_fit() {
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -";
+    let largest = Board::from_fen(&format!("{} 0 2147483647", start)).unwrap();
+    assert_eq!(largest.fullmove_number(), 2147483647);
+    for counters in ["0 2147483648", "4294967295 1", "-1 1", "0 -5"] {
+        assert!(
+            Board::from_fen(&format!("{} {}", start, counters)).is_err(),
+            "{}",
+            counters
+        );
+    }
+}
+
+#[test]
+fn undo_restores_en_passant_target_from_fen() {
+    let fen = "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3";
+    let mut board = board(fen);
+    let hash = board.zobrist_hash();
+    let moves = board.get_moves().len();
+    assert_eq!(moves, 31);
+    let turn = board.parse_uci("g1f3").unwrap();
+    board.make_turn(turn);
+    board.undo_turn();
+    assert_eq!(board.to_fen(), fen);
+    assert_eq!(board.zobrist_hash(), hash);
+    assert_eq!(board.get_moves().len(), moves);
+    assert!(board.parse_uci("e5d6").is_some());
+}
+
+#[test]
+fn undo_restores_en_passant_target_from_setter() {
+    let mut board = board("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq - 0 3");
+    let target = Position::new(5, 3);
+    board.set_en_passant_target(Some(target)).unwrap();
+    let turn = board.parse_uci("g1f3").unwrap();
+    board.make_turn(turn);
+    assert_eq!(board.en_passant_target(), None);
+    board.undo_turn();
+    assert_eq!(board.en_passant_target(), Some(target));
+    assert!(board.parse_uci("e5d6").is_some());
+}
+
+#[test]
+fn cleared_board_has_no_checks_or_moves() {
+    let mut board = Board::from_start();
+    board.clear();
+    assert!(!board.is_check());
+    assert!(board.checkers().is_empty());
+    assert!(board.get_moves().is_empty());
+    assert!(!board.has_legal_move());
+    assert!(board.king_zone(Color::White).is_empty());
+    assert!(matches!(board.get_game_state(), GameState::Draw(_)));
+    assert!(!board.validate_position().is_empty());
+
+    // With only the other player's king placed, there are still no moves
+    board.set_piece(Position::new(7, 4), PieceType::King, Color::Black);
+    board.set_piece(Position::new(0, 0), PieceType::Rook, Color::White);
+    assert!(!board.is_check());
+    assert!(board.get_moves().is_empty());
+    assert!(board.get_piece_moves(Position::new(0, 0)).is_empty());
+}
+
+/// Board from the start, with rules where draws must be claimed
+fn claims_board(movetext: &str) -> Board {
+    let mut board = Board::from_start();
+    board.set_rules(Rules::without_claims());
+    board.apply_movetext(movetext).unwrap();
+    board
+}
+
+#[test]
+fn threefold_repetition_can_be_claimed_with_the_move_that_repeats() {
+    let mut board = claims_board("1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3 Nf6 4. Ng1");
+    assert_eq!(
+        board.claim_draw(None),
+        Err(ClaimError::NoDraw {
+            halfmove_clock: 7,
+            repetitions: 2
+        })
+    );
+    // An incorrect claim leaves the board as it was
+    let fen = board.to_fen();
+    let knight = board.parse_san("Nc6").unwrap();
+    assert!(board.claim_draw(Some(knight)).is_err());
+    assert_eq!(board.to_fen(), fen);
+
+    let repeat = board.parse_san("Ng8").unwrap();
+    assert_eq!(
+        board.claim_draw(Some(repeat)),
+        Ok(DrawReason::ThreefoldRepetition)
+    );
+    assert_eq!(
+        board.get_game_state(),
+        GameState::Draw(DrawReason::ThreefoldRepetition)
+    );
+    assert_eq!(board.claim_draw(None), Err(ClaimError::GameOver));
+    // The claim is kept when looking back through the game
+    board.undo_turn();
+    assert!(board.is_game_over());
+    board.resume();
+    assert!(!board.is_game_over());
+}
+
+#[test]
+fn fifty_move_rule_can_be_claimed() {
+    let mut board = board("4k3/8/8/8/8/8/8/R3K3 w - - 99 80");
+    board.set_rules(Rules::without_claims());
+    assert_eq!(
+        board.claim_draw(None),
+        Err(ClaimError::NoDraw {
+            halfmove_clock: 99,
+            repetitions: 1
+        })
+    );
+    let rook = board.parse_uci("a1a2").unwrap();
+    assert_eq!(board.claim_draw(Some(rook)), Ok(DrawReason::FiftyMoveRule));
+    assert_eq!(board.halfmove_clock(), 100);
+}
+
+#[test]
+fn claims_with_moves_that_are_illegal_or_end_the_game_are_rejected() {
+    let mut board = board("6k1/5ppp/8/8/8/8/8/R5K1 w - - 99 80");
+    board.set_rules(Rules::without_claims());
+    let illegal = board.parse_uci("a1a8").map(|mut turn| {
+        turn.to = Position::new(6, 1);
+        turn
+    });
+    assert_eq!(board.claim_draw(illegal), Err(ClaimError::IllegalTurn));
+    let mate = board.parse_uci("a1a8").unwrap();
+    assert_eq!(board.claim_draw(Some(mate)), Err(ClaimError::EndsGame));
+    assert_eq!(board.halfmove_clock(), 99);
+
+    // With the default rules, the draw has already happened
+    let mut board = Board::from_start();
+    board
+        .apply_movetext("1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3 Nf6 4. Ng1 Ng8")
+        .unwrap();
+    assert_eq!(board.claim_draw(None), Err(ClaimError::GameOver));
+}
@@ -0,0 +1,129 @@
+use chs::{
+    eval::{evaluate, Classical, Evaluator, IncrementalClassical, PhaseWeights},
+    game::{Board, Color},
+    testing::GameGenerator,
+};
+
+/// Returns the FEN of the position with the board flipped and the colors
+/// swapped, which should score the same for the player to move
+fn mirror(fen: &str) -> String {
+    let fields: Vec<&str> = fen.split(' ').collect();
+    let swap = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_uppercase() {
+                    c.to_ascii_lowercase()
+                } else {
+                    c.to_ascii_uppercase()
+                }
+            })
+            .collect()
+    };
+    let placement: Vec<String> = fields[0].split('/').rev().map(swap).collect();
+    let side = if fields[1] == "w" { "b" } else { "w" };
+    format!("{} {} - - 0 1", placement.join("/"), side)
+}
+
+#[test]
+fn default_phase_weights_count_24_at_the_start() {
+    assert_eq!(PhaseWeights::default().max_phase(), 24);
+}
+
+#[test]
+fn mirrored_positions_score_the_same() {
+    for board in GameGenerator::new(5).take(10) {
+        let fen = board.to_fen();
+        let mirrored = Board::from_fen(&mirror(&fen)).unwrap();
+        let unmoved = Board::from_fen(&mirror(&mirror(&fen))).unwrap();
+        assert_eq!(evaluate(&unmoved), evaluate(&mirrored), "{}", fen);
+    }
+}
+
+#[test]
+fn incremental_matches_classical() {
+    let classical = Classical::new();
+    for board in GameGenerator::new(6).take(10) {
+        let mut board = board.clone();
+        board.resume();
+        let mut turns = vec![];
+        while let Some(turn) = board.undo_turn() {
+            turns.push(turn);
+        }
+        let mut incremental = IncrementalClassical::new();
+        incremental.reset(&board);
+        for turn in turns.into_iter().rev() {
+            incremental.make_turn(&board, &turn);
+            board.make_turn(turn);
+            assert_eq!(
+                incremental.evaluate(&board),
+                classical.evaluate(&board),
+                "{}",
+                board.to_fen()
+            );
+        }
+    }
+}
+
+#[test]
+fn custom_phase_weights_change_the_blend() {
+    // With no weights, only the endgame tables count, which value the
+    // central pawn less
+    let board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+    let mut endgame_only = Classical::new();
+    endgame_only.phase = PhaseWeights { weights: [0; 6] };
+    assert_ne!(
+        endgame_only.evaluate(&board),
+        Classical::new().evaluate(&board)
+    );
+}
+
+fn white_score(fen: &str) -> i32 {
+    let board = Board::from_fen(fen).unwrap();
+    match board.whose_turn() {
+        Color::White => evaluate(&board),
+        Color::Black => -evaluate(&board),
+    }
+}
+
+#[test]
+fn pawn_outside_the_square_is_unstoppable() {
+    let caught = white_score("7K/8/1k6/P7/8/8/8/8 w - - 0 1");
+    let unstoppable = white_score("7K/8/8/P7/8/8/8/7k w - - 0 1");
+    assert!(unstoppable - caught > 300, "{} {}", unstoppable, caught);
+}
+
+#[test]
+fn moving_first_can_bring_the_king_into_the_square() {
+    let white_to_move = white_score("7K/8/8/P7/3k4/8/8/8 w - - 0 1");
+    let black_to_move = white_score("7K/8/8/P7/3k4/8/8/8 b - - 0 1");
+    assert!(
+        white_to_move - black_to_move > 300,
+        "{} {}",
+        white_to_move,
+        black_to_move
+    );
+}
+
+#[test]
+fn kings_close_to_a_passed_pawn_help_it() {
+    let supported = white_score("8/8/1K6/P7/8/8/8/6k1 w - - 0 1");
+    let stopped = white_score("8/8/1k6/P7/8/8/8/6K1 b - - 0 1");
+    assert!(supported > stopped, "{} {}", supported, stopped);
+}
+
+#[test]
+fn bishop_of_the_wrong_color_cant_win_with_a_rook_pawn() {
+    // The dark-squared bishop can't drive the king from a8
+    assert_eq!(
+        evaluate(&Board::from_fen("k7/8/8/8/8/8/P7/B3K3 w - - 0 1").unwrap()),
+        0
+    );
+    assert_eq!(
+        evaluate(&Board::from_fen("b3k3/p7/8/8/8/8/8/K7 b - - 0 1").unwrap()),
+        0
+    );
+    // A light-squared bishop covers a8, and the king could also be too far
+    assert!(white_score("k7/8/8/8/8/8/P7/1B2K3 w - - 0 1") > 300);
+    assert!(white_score("8/8/8/8/5k2/8/P7/B3K3 w - - 0 1") > 300);
+}
@@ -0,0 +1,186 @@
+use chs::game::{bishop_attacks, perft, rook_attacks, Bitboard, Board, Move, PieceType, Position};
+
+fn perft_fen(fen: &str, depth: u32) -> u64 {
+    perft(&mut Board::from_fen(fen).unwrap(), depth)
+}
+
+#[test]
+fn perft_start_position() {
+    assert_eq!(perft(&mut Board::from_start(), 3), 8902);
+}
+
+#[test]
+fn perft_kiwipete() {
+    // Castling, en passant and promotions, with sliders on open lines
+    assert_eq!(
+        perft_fen(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            2
+        ),
+        2039
+    );
+}
+
+#[test]
+fn perft_pins_and_en_passant_discovered_check() {
+    assert_eq!(
+        perft_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 4),
+        43238
+    );
+}
+
+#[test]
+fn perft_checks_and_promotions() {
+    assert_eq!(
+        perft_fen(
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            3
+        ),
+        9467
+    );
+}
+
+#[test]
+fn x_fen_castling_rights_use_file_letters_for_inner_rooks() {
+    let write = |fen: &str| Board::from_fen(fen).unwrap().to_fen();
+    // Outermost rooks are written as K and Q, as in standard FEN
+    assert_eq!(
+        write("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9"),
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9"
+    );
+    let inner = "r3k1rr/8/8/8/8/8/8/R3K1RR w GQgq - 0 1";
+    assert_eq!(write("r3k1rr/8/8/8/8/8/8/R3K1RR w GAga - 0 1"), inner);
+    assert_eq!(write(inner), inner);
+}
+
+#[test]
+fn perft_chess960_castling() {
+    assert_eq!(
+        perft_fen(
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            3
+        ),
+        12189
+    );
+}
+
+#[test]
+fn chess960_castling_uci_is_king_onto_rook() {
+    let mut board =
+        Board::from_fen("nr1kbbqr/p1pppppp/1p1n4/8/4P3/3P4/PPP1BPPP/NRNKB1QR b KQkq - 2 3")
+            .unwrap();
+    // The king stepping to c8 isn't castling, which is written as d8b8
+    let step = board.parse_uci("d8c8").unwrap();
+    assert!(step.additional_move.is_none());
+    let castle = board.parse_uci("d8b8").unwrap();
+    assert!(castle.additional_move.is_some());
+    assert_eq!(board.turn_to_uci(&castle), "d8b8");
+    assert_eq!(board.turn_to_uci(&step), "d8c8");
+}
+
+/// Squares attacked along the given directions, found by walking each ray
+/// until it hits a piece
+fn walk_rays(from: Position, occupied: Bitboard, directions: [(i8, i8); 4]) -> Bitboard {
+    let mut attacks = Bitboard::EMPTY;
+    for (r, c) in directions {
+        let mut square = from;
+        while let Some(next) = square.offset(r, c) {
+            square = next;
+            attacks.set(square);
+            if occupied.contains(square) {
+                break;
+            }
+        }
+    }
+    attacks
+}
+
+#[test]
+fn slider_attacks_match_walking_the_rays() {
+    // Sparse, medium and dense occupancies from a fixed xorshift sequence
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut random = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for _ in 0..200 {
+        let occupancies = [
+            random() & random() & random(),
+            random() & random(),
+            random() | random(),
+        ];
+        for occupied in occupancies.map(Bitboard) {
+            for i in 0..64 {
+                let pos = Position::from(i);
+                assert_eq!(
+                    rook_attacks(pos, occupied),
+                    walk_rays(pos, occupied, [(1, 0), (0, 1), (-1, 0), (0, -1)]),
+                );
+                assert_eq!(
+                    bishop_attacks(pos, occupied),
+                    walk_rays(pos, occupied, [(1, 1), (1, -1), (-1, -1), (-1, 1)]),
+                );
+            }
+        }
+    }
+}
+
+/// Check that every legal turn in the position survives packing into a
+/// `Move`, and return the moves
+fn packed_moves(fen: &str) -> Vec<(Move, String)> {
+    let mut board = Board::from_fen(fen).unwrap();
+    board
+        .get_moves()
+        .into_iter()
+        .map(|turn| {
+            let packed = Move::from(&turn);
+            assert_eq!(packed.to_turn(&board), turn);
+            assert_eq!(packed.from_square(), turn.from);
+            assert_eq!(packed.promotion(), turn.promote_to);
+            assert_eq!(packed.is_capture(), turn.capture.is_some());
+            assert_eq!(packed.is_castling(), turn.additional_move.is_some());
+            (packed, board.turn_to_uci(&turn))
+        })
+        .collect()
+}
+
+fn find(moves: &[(Move, String)], uci: &str) -> Move {
+    moves.iter().find(|(_, u)| u == uci).unwrap().0
+}
+
+#[test]
+fn packed_moves_round_trip() {
+    // Promotions with and without captures, for both colors
+    let moves = packed_moves("1n5k/P7/8/8/8/8/p7/1N5K w - - 0 1");
+    let promotion = find(&moves, "a7b8r");
+    assert_eq!(promotion.promotion(), Some(PieceType::Rook));
+    assert!(promotion.is_capture());
+    assert_eq!(find(&moves, "a7a8n").promotion(), Some(PieceType::Knight));
+    let moves = packed_moves("1n5k/P7/8/8/8/8/p7/1N5K b - - 0 1");
+    assert_eq!(find(&moves, "a2b1q").promotion(), Some(PieceType::Queen));
+    assert_eq!(find(&moves, "a2a1b").promotion(), Some(PieceType::Bishop));
+
+    // En passant and double pushes
+    let moves = packed_moves("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3");
+    let en_passant = find(&moves, "e5d6");
+    assert!(en_passant.is_en_passant() && en_passant.is_capture());
+    assert!(find(&moves, "d2d4").is_double_push());
+
+    // Castling on both sides, stored as the king onto its rook
+    let moves = packed_moves("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    assert_eq!(find(&moves, "e1g1").to_square(), Position::new(0, 7));
+    assert_eq!(find(&moves, "e1c1").to_square(), Position::new(0, 0));
+    let moves = packed_moves("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1");
+    assert_eq!(find(&moves, "e8g8").to_square(), Position::new(7, 7));
+    assert_eq!(find(&moves, "e8c8").to_square(), Position::new(7, 0));
+
+    // Chess960 castling, where the king may not move or may pass its rook
+    for fen in [
+        "r1k4r/8/8/8/8/8/8/R1K4R w HAha - 0 1",
+        "nr1kbbqr/p1pppppp/1p1n4/8/4P3/3P4/PPP1BPPP/NRNKB1QR b KQkq - 2 3",
+    ] {
+        assert!(packed_moves(fen).iter().any(|(m, _)| m.is_castling()));
+    }
+}
@@ -0,0 +1,151 @@
+use chs::testing::{check_game, GameGenerator};
+
+fn chess960_games(seed: u64) -> GameGenerator {
+    let mut generator = GameGenerator::new(seed);
+    generator.chess960 = true;
+    generator
+}
+
+#[test]
+fn random_games_round_trip() {
+    for board in GameGenerator::new(1).take(12) {
+        check_game(&board).unwrap();
+    }
+}
+
+#[test]
+fn random_chess960_games_round_trip() {
+    for board in chess960_games(2).take(12) {
+        check_game(&board).unwrap();
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn random_games_round_trip_through_pgn() {
+    use chs::testing::check_pgn;
+
+    for board in GameGenerator::new(3)
+        .take(20)
+        .chain(chess960_games(4).take(20))
+    {
+        check_pgn(&board).unwrap();
+    }
+}
+
+#[cfg(feature = "std")]
+const ROSTER: &str = "[Event \"Club\"]
+[Site \"?\"]
+[Date \"2024.01.31\"]
+[Round \"1\"]
+[White \"A\"]
+[Black \"B\"]
+";
+
+#[cfg(feature = "std")]
+fn validate(pgn: &[u8]) -> Vec<chs::game::PgnCheck> {
+    chs::game::PgnValidator::new(pgn).collect()
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn validator_accepts_a_clean_game() {
+    let pgn = format!("{}[Result \"0-1\"]\n\n1. f3 e5 2. g4 Qh4# 0-1\n", ROSTER);
+    let checks = validate(pgn.as_bytes());
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].line, 1);
+    assert_eq!(checks[0].tags.white, "A");
+    assert!(checks[0].problems.is_empty(), "{:?}", checks[0].problems);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn validator_reports_tag_problems() {
+    use chs::game::PgnProblem;
+
+    let pgn = "[Event \"Club\"]\n[Date \"2024.13.01\"]\n[White \"A\"]\n[White \"C\"]\n\
+               [Result \"2-0\"]\n\n1. e4 *\n";
+    let problems = &validate(pgn.as_bytes())[0].problems;
+    for name in ["Site", "Round", "Black"] {
+        assert!(problems
+            .iter()
+            .any(|p| matches!(p, PgnProblem::MissingTag(tag) if *tag == name)));
+    }
+    assert!(problems
+        .iter()
+        .any(|p| matches!(p, PgnProblem::DuplicateTag(tag) if tag == "White")));
+    assert!(problems
+        .iter()
+        .any(|p| matches!(p, PgnProblem::InvalidDate(_))));
+    assert!(problems
+        .iter()
+        .any(|p| matches!(p, PgnProblem::InvalidResult(result) if result == "2-0")));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn validator_reports_result_problems() {
+    use chs::game::{PgnProblem, PgnResult};
+
+    // The movetext disagrees with the tag
+    let pgn = format!("{}[Result \"1-0\"]\n\n1. e4 e5 1/2-1/2\n", ROSTER);
+    assert!(matches!(
+        validate(pgn.as_bytes())[0].problems[..],
+        [PgnProblem::ResultMismatch(
+            PgnResult::WhiteWins,
+            PgnResult::Draw
+        )]
+    ));
+
+    // Both disagree with the checkmate on the board
+    let pgn = format!("{}[Result \"1-0\"]\n\n1. f3 e5 2. g4 Qh4# 1-0\n", ROSTER);
+    assert!(matches!(
+        validate(pgn.as_bytes())[0].problems[..],
+        [PgnProblem::WrongResult(
+            PgnResult::WhiteWins,
+            PgnResult::BlackWins
+        )]
+    ));
+
+    // No result at all
+    let pgn = format!("{}[Result \"*\"]\n\n1. e4 e5\n", ROSTER);
+    assert!(matches!(
+        validate(pgn.as_bytes())[0].problems[..],
+        [PgnProblem::MissingResult]
+    ));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn validator_reports_illegal_moves_and_keeps_going() {
+    use chs::game::PgnProblem;
+
+    let pgn = format!(
+        "{roster}[Result \"*\"]\n\n1. e4 e5 2. Ke3 *\n\n{roster}[Result \"*\"]\n\n1. d4 *\n",
+        roster = ROSTER
+    );
+    let checks = validate(pgn.as_bytes());
+    assert_eq!(checks.len(), 2);
+    assert!(matches!(
+        checks[0].problems[..],
+        [PgnProblem::Unreadable(_)]
+    ));
+    assert_eq!(checks[1].line, 11);
+    assert!(checks[1].problems.is_empty(), "{:?}", checks[1].problems);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn validator_reports_invalid_utf8() {
+    use chs::game::PgnProblem;
+
+    let mut pgn = b"[Event \"Club\"]\n[Site \"\xff\"]\n".to_vec();
+    pgn.extend_from_slice(&ROSTER.as_bytes()[15..]);
+    pgn.extend_from_slice(b"[Result \"*\"]\n\n1. e4 *\n");
+    let checks = validate(&pgn);
+    assert_eq!(checks.len(), 1);
+    assert!(checks[0]
+        .problems
+        .iter()
+        .any(|p| matches!(p, PgnProblem::InvalidUtf8(2))));
+}
@@ -0,0 +1,345 @@
+use core::time::Duration;
+
+use chs::{
+    game::{Board, Color, GameState, WinReason},
+    search::{
+        analyze_all, find_mate, screen_games, AlphaBeta, Clock, Engine, Score, ScreeningOptions,
+        SearchLimits, SearchOptions, Tablebase, TablebaseResult, TimeManager,
+    },
+};
+
+fn depth(depth: u32) -> SearchLimits {
+    SearchLimits {
+        depth: Some(depth),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn alpha_beta_finds_mate_in_one() {
+    let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    let result = AlphaBeta::new().best_move(&board, &depth(3)).unwrap();
+    assert_eq!(board.turn_to_uci(&result.best), "a1a8");
+    assert_eq!(result.score, Score::Mate(1));
+}
+
+#[test]
+fn alpha_beta_wins_hanging_queen() {
+    let board = Board::from_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+    let result = AlphaBeta::new().best_move(&board, &depth(3)).unwrap();
+    assert_eq!(board.turn_to_uci(&result.best), "d2d5");
+}
+
+#[test]
+fn tablebase_knows_basic_endings() {
+    let tablebase = Tablebase::generate();
+    let probe = |fen: &str| tablebase.probe(&Board::from_fen(fen).unwrap());
+    // Mate in one, and its mirror with Black as the strong side
+    assert_eq!(
+        probe("6k1/8/6K1/8/8/8/8/Q7 w - - 0 1"),
+        Some(TablebaseResult::Win(1))
+    );
+    assert_eq!(
+        probe("q7/8/8/8/8/6k1/8/6K1 b - - 0 1"),
+        Some(TablebaseResult::Win(1))
+    );
+    // Already mated
+    assert_eq!(
+        probe("Q5k1/8/6K1/8/8/8/8/8 b - - 0 1"),
+        Some(TablebaseResult::Loss(0))
+    );
+    // The weak king can take the rook
+    assert_eq!(
+        probe("6k1/6R1/8/8/8/8/8/K7 b - - 0 1"),
+        Some(TablebaseResult::Draw)
+    );
+    // More than three pieces
+    assert_eq!(probe("6k1/8/6K1/8/8/8/P7/Q7 w - - 0 1"), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn mcts_reports_mate_only_for_turns_that_end_the_game() {
+    use chs::search::Mcts;
+
+    let limits = SearchLimits {
+        nodes: Some(500),
+        ..Default::default()
+    };
+    let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    let result = Mcts::new().best_move(&board, &limits).unwrap();
+    assert_eq!(board.turn_to_uci(&result.best), "a1a8");
+    assert_eq!(result.score, Score::Mate(1));
+
+    // Each turn from the start is visited once, and every position scores
+    // as certain for the player to move, which doesn't prove anything
+    let mut certain = Mcts::with_evaluator(|_: &Board| 100_000);
+    let limits = SearchLimits {
+        nodes: Some(1),
+        ..Default::default()
+    };
+    let result = certain.best_move(&Board::from_start(), &limits).unwrap();
+    assert!(!result.score.is_mate(), "{:?}", result.score);
+}
+
+fn clock(millis: u64, increment: u64, moves_to_go: Option<u32>) -> Clock {
+    Clock {
+        time: Duration::from_millis(millis),
+        increment: Duration::from_millis(increment),
+        moves_to_go,
+    }
+}
+
+#[test]
+fn time_is_shared_between_the_moves_to_go() {
+    let manager = TimeManager::new(&clock(60_000, 0, None));
+    let soft = manager.soft_limit();
+    assert!(soft > Duration::from_millis(1_900) && soft < Duration::from_millis(2_100));
+    assert_eq!(manager.hard_limit(), soft * 3);
+}
+
+#[test]
+fn time_limits_never_use_the_whole_clock() {
+    for clock in [
+        // The last move before more time is added
+        clock(10_000, 0, Some(1)),
+        // Most of the time comes from the increment
+        clock(1_000, 2_000, None),
+        clock(1_000, 2_000, Some(1)),
+        // Almost out of time
+        clock(30, 0, None),
+        clock(10, 100, Some(1)),
+    ] {
+        let manager = TimeManager::new(&clock);
+        assert!(manager.soft_limit() <= manager.hard_limit());
+        assert!(manager.hard_limit() <= clock.time / 2, "{:?}", clock);
+    }
+}
+
+#[test]
+fn stable_best_turn_cuts_the_soft_limit() {
+    let mut board = Board::from_start();
+    let e4 = board.parse_uci("e2e4").unwrap();
+    let d4 = board.parse_uci("d2d4").unwrap();
+    let mut manager = TimeManager::new(&clock(60_000, 0, None));
+    let elapsed = manager.soft_limit() * 6 / 10;
+    // The limit is cut by a tenth for each iteration the best turn stays
+    for _ in 0..4 {
+        assert!(!manager.should_stop(e4, elapsed));
+    }
+    assert!(manager.should_stop(e4, elapsed));
+    // A new best turn gives back the whole soft limit
+    assert!(!manager.should_stop(d4, elapsed));
+    // But not past the hard limit
+    assert!(manager.should_stop(e4, manager.hard_limit()));
+}
+
+/// Returns the state of the game after playing the line from the position
+fn after_line(fen: &str, moves: u32) -> Option<(usize, GameState)> {
+    let mut board = Board::from_fen(fen).unwrap();
+    let line = find_mate(&mut board, moves)?;
+    for turn in &line {
+        board.make_turn(*turn);
+    }
+    Some((line.len(), board.get_game_state()))
+}
+
+#[test]
+fn mate_search_finds_mate_in_two() {
+    let fen = "7k/8/8/8/8/8/R7/1R4K1 w - - 0 1";
+    assert_eq!(after_line(fen, 1), None);
+    assert_eq!(
+        after_line(fen, 2),
+        Some((3, GameState::Win(Color::White, WinReason::Checkmate)))
+    );
+}
+
+#[test]
+fn mate_search_finds_mate_in_three() {
+    let fen = "8/8/7k/8/8/8/R7/1R4K1 w - - 0 1";
+    assert_eq!(after_line(fen, 2), None);
+    // Allowing more moves still gives the quickest mate
+    assert_eq!(
+        after_line(fen, 4),
+        Some((5, GameState::Win(Color::White, WinReason::Checkmate)))
+    );
+}
+
+#[test]
+fn mate_search_doesnt_count_stalemate_as_mate() {
+    // Almost every move stalemates Black, and none mates in one or two
+    let fen = "k7/2Q5/8/1K6/8/8/8/8 w - - 0 1";
+    assert_eq!(after_line(fen, 2), None);
+    assert_eq!(
+        after_line(fen, 3),
+        Some((5, GameState::Win(Color::White, WinReason::Checkmate)))
+    );
+    // Qb6 stalemates, but Qb7 mates
+    let mut board = Board::from_fen("k7/8/2K5/8/8/8/8/1Q6 w - - 0 1").unwrap();
+    let line = find_mate(&mut board, 1).unwrap();
+    assert_eq!(board.turn_to_uci(&line[0]), "b1b7");
+}
+
+/// Positions for checking that search options which only speed up the
+/// search don't change its result
+const SEARCH_POSITIONS: [&str; 6] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1",
+    "r1b1kb1r/pppp1ppp/5q2/4n3/3KP3/2N3PN/PPP4P/R1BQ1B1R b kq - 0 1",
+    // Zugzwang, where passing would be better than any move
+    "8/8/1p1r1k2/p1pPN1p1/P3KnP1/1P6/8/3R4 b - - 0 1",
+    "8/8/p1p5/1p5p/1P5p/8/PPP2K1p/4R1rk w - - 0 1",
+];
+
+/// Returns the best turn, in UCI notation, and score found by a search to
+/// the given depth with the given options
+fn search_with(fen: &str, depth: u32, options: SearchOptions) -> (String, Score) {
+    let board = Board::from_fen(fen).unwrap();
+    let mut engine = AlphaBeta::new();
+    engine.options = options;
+    let result = engine
+        .best_move(
+            &board,
+            &SearchLimits {
+                depth: Some(depth),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    (board.turn_to_uci(&result.best), result.score)
+}
+
+/// Options with every optional pruning turned off
+fn exhaustive() -> SearchOptions {
+    SearchOptions {
+        null_move: false,
+        aspiration: false,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn null_move_pruning_keeps_the_result() {
+    let null_move = SearchOptions {
+        null_move: true,
+        ..exhaustive()
+    };
+    for fen in SEARCH_POSITIONS {
+        assert_eq!(
+            search_with(fen, 4, null_move),
+            search_with(fen, 4, exhaustive()),
+            "{}",
+            fen
+        );
+    }
+}
+
+#[test]
+fn aspiration_windows_keep_the_result() {
+    // A narrow window, so that most iterations fall outside it at first
+    let aspiration = SearchOptions {
+        aspiration: true,
+        aspiration_window: 10,
+        ..exhaustive()
+    };
+    for fen in SEARCH_POSITIONS {
+        assert_eq!(
+            search_with(fen, 4, aspiration),
+            search_with(fen, 4, exhaustive()),
+            "{}",
+            fen
+        );
+    }
+}
+
+#[test]
+fn warm_start_follows_the_given_line() {
+    let mut board =
+        Board::from_fen("r1b1kb1r/pppp1ppp/5q2/4n3/3KP3/2N3PN/PPP4P/R1BQ1B1R b kq - 0 1").unwrap();
+    let limits = SearchLimits {
+        depth: Some(4),
+        ..Default::default()
+    };
+    let cold = AlphaBeta::new().best_move(&board, &limits).unwrap();
+
+    // Searching again from the line found gives the same result sooner
+    let mut engine = AlphaBeta::new();
+    engine.warm_start(&board, &cold.pv);
+    let warm = engine.best_move(&board, &limits).unwrap();
+    assert_eq!((warm.best, warm.score), (cold.best, cold.score));
+    assert!(warm.nodes < cold.nodes);
+
+    // A line for a different position is ignored
+    let mut engine = AlphaBeta::new();
+    engine.warm_start(&Board::from_start(), &cold.pv);
+    assert_eq!(engine.best_move(&board, &limits).unwrap().nodes, cold.nodes);
+
+    // As is the rest of a line after a turn that isn't legal
+    board.make_turn(cold.pv[0]);
+    let mut engine = AlphaBeta::new();
+    engine.warm_start(&board, &cold.pv);
+    let cold = AlphaBeta::new().best_move(&board, &limits).unwrap();
+    assert_eq!(engine.best_move(&board, &limits).unwrap().nodes, cold.nodes);
+}
+
+/// Options for screening every turn of a player at the given depths
+fn screen_every_turn(depths: &[u32]) -> ScreeningOptions {
+    ScreeningOptions {
+        depths: depths.to_vec(),
+        skip_moves: 0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn screening_an_engine_finds_every_turn_matches() {
+    // Both sides play the turn `analyze_all` scores best at depth 2
+    let mut board = Board::from_start();
+    for _ in 0..12 {
+        let best = analyze_all(&mut board, &depth(2))[0].turn;
+        board.make_turn(best);
+    }
+    let reports = screen_games([(&board, Color::White)], &screen_every_turn(&[2]));
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+    assert_eq!(report.depth, 2);
+    assert_eq!(report.positions, 6);
+    assert_eq!(report.matches, 6);
+    assert_eq!(report.near_matches, 6);
+    assert_eq!(report.centipawn_loss, 0);
+    assert_eq!(report.match_rate(), 1.0);
+    assert_eq!(report.weighted_match_rate(), 1.0);
+}
+
+#[test]
+fn screening_counts_blunders() {
+    let mut board = Board::from_start();
+    board.apply_movetext("1. f3 e5 2. g4 Qh4#").unwrap();
+    let options = screen_every_turn(&[1, 2]);
+    let reports = screen_games([(&board, Color::White)], &options);
+    assert_eq!(reports.len(), 2);
+    for report in &reports {
+        assert_eq!(report.positions, 2);
+        assert_eq!(report.matches, 0);
+        assert_eq!(report.near_match_rate(), 0.0);
+    }
+    // Only the deeper search sees that 2. g4 allows mate
+    let deep = &reports[1];
+    assert_eq!(deep.depth, 2);
+    assert!(reports[0].average_centipawn_loss() < 100.0);
+    assert!(deep.average_centipawn_loss() >= 500.0);
+
+    // Black finds the mate, and skipping both of Black's turns leaves
+    // nothing to compare
+    let reports = screen_games([(&board, Color::Black)], &options);
+    assert_eq!(reports[1].positions, 2);
+    assert!(reports[1].matches >= 1);
+    let skipped = ScreeningOptions {
+        skip_moves: 2,
+        ..options
+    };
+    let reports = screen_games([(&board, Color::Black)], &skipped);
+    assert_eq!(reports[0].positions, 0);
+    assert_eq!(reports[0].match_rate(), 0.0);
+}
@@ -0,0 +1,98 @@
+use chs::game::{score_from_tt, score_to_tt, Board, Bound, Move, TranspositionTable};
+use chs::search::MATE;
+
+fn some_move() -> Move {
+    let mut board = Board::from_start();
+    Move::from(&board.parse_uci("e2e4").unwrap())
+}
+
+#[test]
+fn stored_entries_are_found() {
+    let mut tt = TranspositionTable::with_buckets(16);
+    assert!(tt.probe(7).is_none());
+    tt.store(7, 5, 42, Bound::Exact, Some(some_move()));
+    let entry = tt.probe(7).unwrap();
+    assert_eq!(entry.key, 7);
+    assert_eq!(entry.depth, 5);
+    assert_eq!(entry.score, 42);
+    assert_eq!(entry.best_move, Some(some_move()));
+    // A different key in the same bucket isn't confused with it
+    assert!(tt.probe(7 + 16).is_none());
+}
+
+#[test]
+fn bounds_are_stored() {
+    let mut tt = TranspositionTable::with_buckets(16);
+    for (key, bound) in [(1, Bound::Exact), (2, Bound::Lower), (3, Bound::Upper)] {
+        tt.store(key, 1, 0, bound, None);
+    }
+    assert_eq!(tt.probe(1).unwrap().bound, Bound::Exact);
+    assert_eq!(tt.probe(2).unwrap().bound, Bound::Lower);
+    assert_eq!(tt.probe(3).unwrap().bound, Bound::Upper);
+}
+
+#[test]
+fn storing_the_same_position_replaces_it_and_keeps_its_move() {
+    let mut tt = TranspositionTable::with_buckets(16);
+    tt.store(7, 5, 42, Bound::Lower, Some(some_move()));
+    tt.store(7, 3, -10, Bound::Upper, None);
+    let entry = tt.probe(7).unwrap();
+    assert_eq!(
+        (entry.depth, entry.score, entry.bound),
+        (3, -10, Bound::Upper)
+    );
+    assert_eq!(entry.best_move, Some(some_move()));
+}
+
+#[test]
+fn full_bucket_replaces_the_shallowest_entry() {
+    // A single bucket, so every key shares it
+    let mut tt = TranspositionTable::with_buckets(1);
+    for (key, depth) in [(1, 6), (2, 2), (3, 8), (4, 4)] {
+        tt.store(key, depth, 0, Bound::Exact, None);
+    }
+    tt.store(5, 1, 0, Bound::Exact, None);
+    assert!(tt.probe(2).is_none());
+    for key in [1, 3, 4, 5] {
+        assert!(tt.probe(key).is_some());
+    }
+}
+
+#[test]
+fn full_bucket_replaces_entries_from_earlier_searches_first() {
+    let mut tt = TranspositionTable::with_buckets(1);
+    tt.store(1, 10, 0, Bound::Exact, None);
+    tt.new_search();
+    for (key, depth) in [(2, 1), (3, 1), (4, 1)] {
+        tt.store(key, depth, 0, Bound::Exact, None);
+    }
+    // The old entry goes, even though it is the deepest
+    tt.store(5, 1, 0, Bound::Exact, None);
+    assert!(tt.probe(1).is_none());
+    for key in [2, 3, 4, 5] {
+        assert!(tt.probe(key).is_some());
+    }
+}
+
+#[test]
+fn clear_removes_every_entry() {
+    let mut tt = TranspositionTable::with_buckets(16);
+    tt.store(7, 5, 42, Bound::Exact, None);
+    tt.clear();
+    assert!(tt.probe(7).is_none());
+    assert_eq!(tt.hashfull(), 0);
+}
+
+#[test]
+fn mate_scores_count_from_the_stored_position() {
+    // Mate in 3 plies from a position found 4 plies from the root
+    let score = MATE - 7;
+    let stored = score_to_tt(score, 4);
+    assert_eq!(stored, MATE - 3);
+    // Reached again 2 plies from the root, it is mate in 5 plies from there
+    assert_eq!(score_from_tt(stored, 2), MATE - 5);
+    assert_eq!(score_from_tt(score_to_tt(-score, 4), 2), -(MATE - 5));
+    // Other scores are unchanged
+    assert_eq!(score_to_tt(150, 4), 150);
+    assert_eq!(score_from_tt(-150, 2), -150);
+}